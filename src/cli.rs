@@ -0,0 +1,117 @@
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub command: String,
+    pub filename: Option<String>,
+    pub source: Option<String>,
+    pub json: bool,
+    pub normalize_numbers: bool,
+}
+
+pub fn parse<I>(args: I) -> Result<Args, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let program = args.next().unwrap_or_default();
+
+    let Some(command) = args.next() else {
+        return Err(CliError::Usage(program));
+    };
+
+    let mut filename = None;
+    let mut source = None;
+    let mut json = false;
+    let mut normalize_numbers = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-e" | "--eval" => source = Some(args.next().ok_or(CliError::MissingSource)?),
+            "--json" => json = true,
+            "--normalize-numbers" => normalize_numbers = true,
+            _ if filename.is_none() => filename = Some(arg),
+            _ => return Err(CliError::Usage(program)),
+        }
+    }
+
+    // One program per run: inline source and a file would compete.
+    if source.is_some() && filename.is_some() {
+        return Err(CliError::Usage(program));
+    }
+
+    Ok(Args {
+        command,
+        filename,
+        source,
+        json,
+        normalize_numbers,
+    })
+}
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error(
+        "Usage: {0} <tokenize|parse|evaluate|run|repl> [--json] [--normalize-numbers] [filename | -e <source>]"
+    )]
+    Usage(String),
+    #[error("Expected source text after -e.")]
+    MissingSource,
+}
+
+impl CliError {
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => 1,
+            Self::MissingSource => 64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Args, CliError> {
+        parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn normalize_numbers_flag_is_off_by_default() {
+        let args = parse_args(&["interpreter", "tokenize", "test.lox"]).unwrap();
+        assert!(!args.normalize_numbers);
+
+        let args =
+            parse_args(&["interpreter", "tokenize", "--normalize-numbers", "test.lox"]).unwrap();
+        assert!(args.normalize_numbers);
+        assert_eq!(args.filename.as_deref(), Some("test.lox"));
+    }
+
+    #[test]
+    fn eval_flag_takes_the_next_argument_as_source() {
+        let args = parse_args(&["interpreter", "evaluate", "-e", "1 + 2"]).unwrap();
+        assert_eq!(args.source.as_deref(), Some("1 + 2"));
+        assert_eq!(args.filename, None);
+
+        let args = parse_args(&["interpreter", "run", "--eval", "print 1;"]).unwrap();
+        assert_eq!(args.source.as_deref(), Some("print 1;"));
+    }
+
+    #[test]
+    fn eval_flag_without_source_exits_64() {
+        let error = parse_args(&["interpreter", "evaluate", "-e"]).unwrap_err();
+        assert!(matches!(error, CliError::MissingSource));
+        assert_eq!(error.exit_code(), 64);
+    }
+
+    #[test]
+    fn eval_flag_with_a_filename_is_a_usage_error() {
+        for args in [
+            ["interpreter", "run", "-e", "print 1;", "test.lox"],
+            ["interpreter", "run", "test.lox", "-e", "print 1;"],
+        ] {
+            let error = parse_args(&args).unwrap_err();
+            assert!(matches!(error, CliError::Usage(_)), "{args:?}");
+        }
+    }
+}
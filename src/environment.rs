@@ -0,0 +1,174 @@
+use crate::{
+    interpreter::{RuntimeError, RuntimeErrorKind},
+    token::Token,
+    value::LiteralValue,
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    rc::Rc,
+};
+
+/// Shared, mutable handle to a scope. Functions keep the handle of the scope
+/// they were declared in, so a closure and the code around it see the same
+/// variables even after the declaring block or call has returned.
+pub type EnvironmentRef<'a> = Rc<RefCell<Environment<'a>>>;
+
+#[derive(Debug, Default)]
+pub struct Environment<'a> {
+    values: HashMap<String, LiteralValue<'a>>,
+    constants: HashSet<String>,
+    enclosing: Option<EnvironmentRef<'a>>,
+}
+
+impl<'a> Environment<'a> {
+    pub fn new() -> EnvironmentRef<'a> {
+        Rc::new(RefCell::new(Self::default()))
+    }
+
+    pub fn with_enclosing(enclosing: EnvironmentRef<'a>) -> EnvironmentRef<'a> {
+        Rc::new(RefCell::new(Self {
+            enclosing: Some(enclosing),
+            ..Self::default()
+        }))
+    }
+
+    pub fn define(&mut self, name: &str, value: LiteralValue<'a>) {
+        self.constants.remove(name);
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn define_const(&mut self, name: &str, value: LiteralValue<'a>) {
+        self.constants.insert(name.to_string());
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &Token<'_>) -> Result<LiteralValue<'a>, RuntimeError> {
+        if let Some(value) = self.values.get(name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    pub fn assign(
+        &mut self,
+        name: &Token<'_>,
+        value: LiteralValue<'a>,
+    ) -> Result<(), RuntimeError> {
+        if let Some(slot) = self.values.get_mut(name.lexeme) {
+            if self.constants.contains(name.lexeme) {
+                return Err(Self::constant(name));
+            }
+
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    pub fn get_own(&self, name: &str) -> Option<LiteralValue<'a>> {
+        self.values.get(name).cloned()
+    }
+
+    pub fn ancestor(environment: &EnvironmentRef<'a>, distance: usize) -> EnvironmentRef<'a> {
+        let mut environment = Rc::clone(environment);
+
+        for _ in 0..distance {
+            let Some(enclosing) = environment.borrow().enclosing.clone() else {
+                break;
+            };
+            environment = enclosing;
+        }
+
+        environment
+    }
+
+    /// The outermost scope of the chain: the globals of the file the
+    /// environment's code was declared in.
+    pub fn root(environment: &EnvironmentRef<'a>) -> EnvironmentRef<'a> {
+        Self::ancestor(environment, usize::MAX)
+    }
+
+    /// One line per scope, innermost first: its distance from
+    /// `environment`, then its variables sorted by name. Natives are left
+    /// out so the globals line stays readable.
+    pub fn dump(environment: &EnvironmentRef<'a>) -> String {
+        let mut dump = String::new();
+        let mut scope = Some(Rc::clone(environment));
+        let mut depth = 0;
+
+        while let Some(current) = scope {
+            let current = current.borrow();
+            let mut variables: Vec<_> = current
+                .values
+                .iter()
+                .filter(|(_, value)| !matches!(value, LiteralValue::NativeFunction(_)))
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect();
+            variables.sort();
+
+            let _ = writeln!(dump, "{depth}: {}", variables.join(", "));
+            scope = current.enclosing.clone();
+            depth += 1;
+        }
+
+        dump
+    }
+
+    pub fn get_at(
+        environment: &EnvironmentRef<'a>,
+        distance: usize,
+        name: &Token<'_>,
+    ) -> Result<LiteralValue<'a>, RuntimeError> {
+        Self::ancestor(environment, distance)
+            .borrow()
+            .values
+            .get(name.lexeme)
+            .cloned()
+            .ok_or_else(|| Self::undefined(name))
+    }
+
+    pub fn assign_at(
+        environment: &EnvironmentRef<'a>,
+        distance: usize,
+        name: &Token<'_>,
+        value: LiteralValue<'a>,
+    ) -> Result<(), RuntimeError> {
+        let ancestor = Self::ancestor(environment, distance);
+        let mut ancestor = ancestor.borrow_mut();
+
+        if ancestor.constants.contains(name.lexeme) {
+            return Err(Self::constant(name));
+        }
+
+        let Some(slot) = ancestor.values.get_mut(name.lexeme) else {
+            return Err(Self::undefined(name));
+        };
+        *slot = value;
+
+        Ok(())
+    }
+
+    fn constant(name: &Token<'_>) -> RuntimeError {
+        RuntimeError::new(
+            RuntimeErrorKind::AssignToConstant(name.lexeme.to_string()),
+            name.line,
+        )
+    }
+
+    fn undefined(name: &Token<'_>) -> RuntimeError {
+        RuntimeError::new(
+            RuntimeErrorKind::UndefinedVariable(name.lexeme.to_string()),
+            name.line,
+        )
+    }
+}
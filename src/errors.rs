@@ -16,4 +16,7 @@ pub enum InterpreterError {
 
     #[error("{0}")]
     Parse(#[from] crate::parser::ParseError),
+
+    #[error("{0}")]
+    Runtime(#[from] crate::interpreter::RuntimeError),
 }
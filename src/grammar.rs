@@ -1,4 +1,20 @@
-use std::fmt;
+use crate::{
+    token::{Token, TokenKind},
+    value::format_literal_number,
+};
+use std::{
+    borrow::Cow,
+    fmt,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub type ExprId = usize;
+
+pub fn next_expr_id() -> ExprId {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 #[derive(Debug, Clone)]
 
@@ -9,27 +25,155 @@ pub enum Expr<'a> {
         left_operand: Box<Self>,
         operator: Operator,
         right_operand: Box<Self>,
+        line: usize,
+    },
+    Logical {
+        left_operand: Box<Self>,
+        operator: Operator,
+        right_operand: Box<Self>,
     },
     Unary {
         operator: Operator,
         operand: Box<Self>,
+        line: usize,
+    },
+    Ternary {
+        condition: Box<Self>,
+        then_branch: Box<Self>,
+        else_branch: Box<Self>,
+    },
+    Variable {
+        id: ExprId,
+        name: Token<'a>,
+    },
+    Assignment {
+        id: ExprId,
+        name: Token<'a>,
+        value: Box<Self>,
+    },
+    Call {
+        callee: Box<Self>,
+        paren: Token<'a>,
+        arguments: Vec<Self>,
+    },
+    This {
+        id: ExprId,
+        keyword: Token<'a>,
+    },
+    Super {
+        id: ExprId,
+        keyword: Token<'a>,
+        method: Token<'a>,
+    },
+    Get {
+        object: Box<Self>,
+        name: Token<'a>,
+    },
+    Set {
+        object: Box<Self>,
+        name: Token<'a>,
+        value: Box<Self>,
+    },
+    ListLiteral(Vec<Self>),
+    Index {
+        target: Box<Self>,
+        bracket: Token<'a>,
+        index: Box<Self>,
     },
+    IndexAssignment {
+        target: Box<Self>,
+        bracket: Token<'a>,
+        index: Box<Self>,
+        value: Box<Self>,
+    },
+    Comma(Vec<Self>),
+    Postfix {
+        id: ExprId,
+        name: Token<'a>,
+        operator: Operator,
+    },
+    /// An anonymous `fun (params) { body }`; its name token is the `fun`
+    /// keyword.
+    Lambda(Rc<FunctionDeclaration<'a>>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Statement<'a> {
+    Expression(Expr<'a>),
+    Print {
+        keyword: Token<'a>,
+        values: Vec<Expr<'a>>,
+    },
+    Var {
+        name: Token<'a>,
+        initializer: Option<Expr<'a>>,
+    },
+    Const {
+        name: Token<'a>,
+        initializer: Expr<'a>,
+    },
+    Block(Vec<Self>),
+    Function(Rc<FunctionDeclaration<'a>>),
+    Class {
+        name: Token<'a>,
+        superclass: Option<Expr<'a>>,
+        methods: Vec<Rc<FunctionDeclaration<'a>>>,
+    },
+    If {
+        condition: Expr<'a>,
+        then_branch: Box<Self>,
+        else_branch: Option<Box<Self>>,
+    },
+    While {
+        condition: Expr<'a>,
+        body: Box<Self>,
+        increment: Option<Expr<'a>>,
+    },
+    Break,
+    Continue,
+    Return {
+        keyword: Token<'a>,
+        value: Option<Expr<'a>>,
+    },
+    Import {
+        keyword: Token<'a>,
+        path: Cow<'a, str>,
+        alias: Option<Token<'a>>,
+    },
+    StaticAssert {
+        keyword: Token<'a>,
+        condition: Expr<'a>,
+    },
+}
+
+#[derive(Debug)]
+pub struct FunctionDeclaration<'a> {
+    pub name: Token<'a>,
+    pub params: Vec<Token<'a>>,
+    pub body: Vec<Statement<'a>>,
 }
 
 #[derive(Debug, Clone)]
 pub enum Literal<'a> {
     Number(f64),
-    String(&'a str),
+    String(Cow<'a, str>),
     Boolean(bool),
     Nil,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 
 pub enum Operator {
     Add,
     Subtract,
+    Multiply,
     Divide,
+    Modulo,
+    Power,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Not,
     GreaterThan,
     LessThan,
     GreaterThanEqual,
@@ -40,6 +184,403 @@ pub enum Operator {
     Or,
 }
 
+impl Operator {
+    pub const fn from_token_kind(kind: TokenKind) -> Option<Self> {
+        let operator = match kind {
+            TokenKind::Plus => Self::Add,
+            TokenKind::Minus => Self::Subtract,
+            TokenKind::Star => Self::Multiply,
+            TokenKind::Slash => Self::Divide,
+            TokenKind::Percent => Self::Modulo,
+            TokenKind::StarStar => Self::Power,
+            TokenKind::Ampersand => Self::BitAnd,
+            TokenKind::Pipe => Self::BitOr,
+            TokenKind::Caret => Self::BitXor,
+            TokenKind::Bang => Self::Not,
+            TokenKind::Greater => Self::GreaterThan,
+            TokenKind::Less => Self::LessThan,
+            TokenKind::GreaterEqual => Self::GreaterThanEqual,
+            TokenKind::LessEqual => Self::LessThanEqual,
+            TokenKind::EqualEqual => Self::EqualEqual,
+            TokenKind::BangEqual => Self::NotEqual,
+            TokenKind::And => Self::And,
+            TokenKind::Or => Self::Or,
+            _ => return None,
+        };
+
+        Some(operator)
+    }
+}
+
+impl Default for Expr<'_> {
+    fn default() -> Self {
+        Self::Literal(Literal::Nil)
+    }
+}
+
+/// Frees nested operator expressions with an explicit stack, so dropping a
+/// deeply nested chain like `1 - 1 - ... - 1` can't overflow the Rust stack.
+impl Drop for Expr<'_> {
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        self.take_nested_operands(&mut pending);
+
+        while let Some(mut expr) = pending.pop() {
+            expr.take_nested_operands(&mut pending);
+        }
+    }
+}
+
+impl Expr<'_> {
+    const fn is_operator(&self) -> bool {
+        matches!(
+            self,
+            Self::Grouping(_)
+                | Self::Unary { .. }
+                | Self::Binary { .. }
+                | Self::Logical { .. }
+                | Self::Ternary { .. }
+        )
+    }
+
+    /// Moves operands that are operator expressions themselves into
+    /// `pending`, leaving `nil` in their place.
+    fn take_nested_operands(&mut self, pending: &mut Vec<Self>) {
+        let operands = match self {
+            Self::Grouping(operand) | Self::Unary { operand, .. } => vec![operand],
+            Self::Binary {
+                left_operand,
+                right_operand,
+                ..
+            }
+            | Self::Logical {
+                left_operand,
+                right_operand,
+                ..
+            } => vec![left_operand, right_operand],
+            Self::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => vec![condition, then_branch, else_branch],
+            _ => return,
+        };
+
+        for operand in operands {
+            if operand.is_operator() {
+                pending.push(*std::mem::take(operand));
+            }
+        }
+    }
+
+    /// The line of the first token in the expression that records one.
+    /// Literals don't keep their token, so `None` for an expression built
+    /// only from literals.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Literal(_) => None,
+            Self::Binary { line, .. } | Self::Unary { line, .. } => Some(*line),
+            Self::Variable { name, .. }
+            | Self::Assignment { name, .. }
+            | Self::Postfix { name, .. } => Some(name.line),
+            Self::This { keyword, .. } | Self::Super { keyword, .. } => Some(keyword.line),
+            Self::Call { paren, .. } => Some(paren.line),
+            Self::Lambda(declaration) => Some(declaration.name.line),
+            Self::Index { bracket, .. } | Self::IndexAssignment { bracket, .. } => {
+                Some(bracket.line)
+            }
+            Self::Grouping(expr) => expr.line(),
+            Self::Logical { left_operand, .. } => left_operand.line(),
+            Self::Ternary { condition, .. } => condition.line(),
+            Self::Get { object, .. } | Self::Set { object, .. } => object.line(),
+            Self::ListLiteral(exprs) | Self::Comma(exprs) => exprs.iter().find_map(Self::line),
+        }
+    }
+
+    /// Whether the expression is built only from literals and operators, so
+    /// its value is known without running the program.
+    pub fn is_constant(&self) -> bool {
+        match self {
+            Self::Literal(_) => true,
+            Self::Grouping(expr) | Self::Unary { operand: expr, .. } => expr.is_constant(),
+            Self::Binary {
+                left_operand,
+                right_operand,
+                ..
+            }
+            | Self::Logical {
+                left_operand,
+                right_operand,
+                ..
+            } => left_operand.is_constant() && right_operand.is_constant(),
+            Self::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => condition.is_constant() && then_branch.is_constant() && else_branch.is_constant(),
+            _ => false,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Literal(literal) => {
+                format!(r#"{{"type":"Literal","value":{}}}"#, literal.to_json())
+            }
+            Self::Grouping(expr) => {
+                format!(r#"{{"type":"Grouping","expression":{}}}"#, expr.to_json())
+            }
+            Self::Binary {
+                left_operand,
+                operator,
+                right_operand,
+                ..
+            } => format!(
+                r#"{{"type":"Binary","op":{},"left":{},"right":{}}}"#,
+                json_string(&operator.to_string()),
+                left_operand.to_json(),
+                right_operand.to_json()
+            ),
+            Self::Logical {
+                left_operand,
+                operator,
+                right_operand,
+            } => format!(
+                r#"{{"type":"Logical","op":{},"left":{},"right":{}}}"#,
+                json_string(&operator.to_string()),
+                left_operand.to_json(),
+                right_operand.to_json()
+            ),
+            Self::Unary {
+                operator, operand, ..
+            } => format!(
+                r#"{{"type":"Unary","op":{},"operand":{}}}"#,
+                json_string(&operator.to_string()),
+                operand.to_json()
+            ),
+            Self::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => format!(
+                r#"{{"type":"Ternary","condition":{},"then":{},"else":{}}}"#,
+                condition.to_json(),
+                then_branch.to_json(),
+                else_branch.to_json()
+            ),
+            Self::Variable { name, .. } => {
+                format!(
+                    r#"{{"type":"Variable","name":{}}}"#,
+                    json_string(name.lexeme)
+                )
+            }
+            Self::Assignment { name, value, .. } => format!(
+                r#"{{"type":"Assignment","name":{},"value":{}}}"#,
+                json_string(name.lexeme),
+                value.to_json()
+            ),
+            Self::Call {
+                callee, arguments, ..
+            } => format!(
+                r#"{{"type":"Call","callee":{},"arguments":{}}}"#,
+                callee.to_json(),
+                json_array(arguments.iter().map(Self::to_json))
+            ),
+            Self::Lambda(declaration) => format!(
+                r#"{{"type":"Lambda","params":{},"body":{}}}"#,
+                json_array(
+                    declaration
+                        .params
+                        .iter()
+                        .map(|param| json_string(param.lexeme))
+                ),
+                json_array(declaration.body.iter().map(Statement::to_json))
+            ),
+            Self::This { .. } => r#"{"type":"This"}"#.to_string(),
+            Self::Super { method, .. } => {
+                format!(
+                    r#"{{"type":"Super","method":{}}}"#,
+                    json_string(method.lexeme)
+                )
+            }
+            Self::Get { object, name } => format!(
+                r#"{{"type":"Get","object":{},"name":{}}}"#,
+                object.to_json(),
+                json_string(name.lexeme)
+            ),
+            Self::Set {
+                object,
+                name,
+                value,
+            } => format!(
+                r#"{{"type":"Set","object":{},"name":{},"value":{}}}"#,
+                object.to_json(),
+                json_string(name.lexeme),
+                value.to_json()
+            ),
+            Self::ListLiteral(elements) => format!(
+                r#"{{"type":"List","elements":{}}}"#,
+                json_array(elements.iter().map(Self::to_json))
+            ),
+            Self::Index { target, index, .. } => format!(
+                r#"{{"type":"Index","target":{},"index":{}}}"#,
+                target.to_json(),
+                index.to_json()
+            ),
+            Self::IndexAssignment {
+                target,
+                index,
+                value,
+                ..
+            } => format!(
+                r#"{{"type":"IndexAssignment","target":{},"index":{},"value":{}}}"#,
+                target.to_json(),
+                index.to_json(),
+                value.to_json()
+            ),
+            Self::Comma(expressions) => format!(
+                r#"{{"type":"Comma","expressions":{}}}"#,
+                json_array(expressions.iter().map(Self::to_json))
+            ),
+            Self::Postfix { name, operator, .. } => format!(
+                r#"{{"type":"Postfix","op":{},"name":{}}}"#,
+                json_string(&format!("{operator}{operator}")),
+                json_string(name.lexeme)
+            ),
+        }
+    }
+}
+
+impl Statement<'_> {
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Expression(expr) => {
+                format!(r#"{{"type":"Expression","expression":{}}}"#, expr.to_json())
+            }
+            Self::Print { values, .. } => format!(
+                r#"{{"type":"Print","expressions":{}}}"#,
+                json_array(values.iter().map(Expr::to_json))
+            ),
+            Self::Var { name, initializer } => format!(
+                r#"{{"type":"Var","name":{},"initializer":{}}}"#,
+                json_string(name.lexeme),
+                initializer
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), Expr::to_json)
+            ),
+            Self::Const { name, initializer } => format!(
+                r#"{{"type":"Const","name":{},"initializer":{}}}"#,
+                json_string(name.lexeme),
+                initializer.to_json()
+            ),
+            Self::Block(statements) => format!(
+                r#"{{"type":"Block","statements":{}}}"#,
+                json_array(statements.iter().map(Self::to_json))
+            ),
+            Self::Function(declaration) => declaration.to_json(),
+            Self::Class {
+                name,
+                superclass,
+                methods,
+            } => format!(
+                r#"{{"type":"Class","name":{},"superclass":{},"methods":{}}}"#,
+                json_string(name.lexeme),
+                superclass
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), Expr::to_json),
+                json_array(methods.iter().map(|method| method.to_json()))
+            ),
+            Self::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => format!(
+                r#"{{"type":"If","condition":{},"then":{},"else":{}}}"#,
+                condition.to_json(),
+                then_branch.to_json(),
+                else_branch
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), |branch| branch.to_json())
+            ),
+            Self::While {
+                condition,
+                body,
+                increment,
+            } => format!(
+                r#"{{"type":"While","condition":{},"body":{},"increment":{}}}"#,
+                condition.to_json(),
+                body.to_json(),
+                increment
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), Expr::to_json)
+            ),
+            Self::Break => r#"{"type":"Break"}"#.to_string(),
+            Self::Continue => r#"{"type":"Continue"}"#.to_string(),
+            Self::Return { value, .. } => format!(
+                r#"{{"type":"Return","value":{}}}"#,
+                value
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), Expr::to_json)
+            ),
+            Self::Import { path, alias, .. } => format!(
+                r#"{{"type":"Import","path":{},"alias":{}}}"#,
+                json_string(path),
+                alias
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), |alias| json_string(alias.lexeme))
+            ),
+            Self::StaticAssert { condition, .. } => format!(
+                r#"{{"type":"StaticAssert","condition":{}}}"#,
+                condition.to_json()
+            ),
+        }
+    }
+}
+
+impl FunctionDeclaration<'_> {
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"type":"Function","name":{},"params":{},"body":{}}}"#,
+            json_string(self.name.lexeme),
+            json_array(self.params.iter().map(|param| json_string(param.lexeme))),
+            json_array(self.body.iter().map(Statement::to_json))
+        )
+    }
+}
+
+impl Literal<'_> {
+    fn to_json(&self) -> String {
+        match self {
+            Self::Number(number) if number.is_finite() => format!("{number}"),
+            Self::Number(_) | Self::Nil => "null".to_string(),
+            Self::String(string) => json_string(string),
+            Self::Boolean(bool) => bool.to_string(),
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_array(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
 impl fmt::Display for Expr<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -49,8 +590,71 @@ impl fmt::Display for Expr<'_> {
                 left_operand,
                 operator,
                 right_operand,
-            } => write!(f, "{operator} {left_operand} {right_operand}"),
-            Self::Unary { operator, operand } => write!(f, "{operator} {operand}"),
+                ..
+            }
+            | Self::Logical {
+                left_operand,
+                operator,
+                right_operand,
+            } => write!(f, "({operator} {left_operand} {right_operand})"),
+            Self::Unary {
+                operator, operand, ..
+            } => write!(f, "({operator} {operand})"),
+            Self::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => write!(f, "(?: {condition} {then_branch} {else_branch})"),
+            Self::Variable { name, .. } => write!(f, "{}", name.lexeme),
+            Self::Assignment { name, value, .. } => write!(f, "(= {} {value})", name.lexeme),
+            Self::Call {
+                callee, arguments, ..
+            } => {
+                write!(f, "(call {callee}")?;
+                for argument in arguments {
+                    write!(f, " {argument}")?;
+                }
+                write!(f, ")")
+            }
+            Self::This { .. } => write!(f, "this"),
+            Self::Super { method, .. } => write!(f, "(super {})", method.lexeme),
+            Self::Get { object, name } => write!(f, "(. {object} {})", name.lexeme),
+            Self::Set {
+                object,
+                name,
+                value,
+            } => write!(f, "(= (. {object} {}) {value})", name.lexeme),
+            Self::ListLiteral(elements) => {
+                write!(f, "(list")?;
+                for element in elements {
+                    write!(f, " {element}")?;
+                }
+                write!(f, ")")
+            }
+            Self::Index { target, index, .. } => write!(f, "(index {target} {index})"),
+            Self::IndexAssignment {
+                target,
+                index,
+                value,
+                ..
+            } => write!(f, "(= (index {target} {index}) {value})"),
+            Self::Postfix { name, operator, .. } => {
+                write!(f, "({operator}{operator} {})", name.lexeme)
+            }
+            Self::Comma(expressions) => {
+                write!(f, "(comma")?;
+                for expr in expressions {
+                    write!(f, " {expr}")?;
+                }
+                write!(f, ")")
+            }
+            Self::Lambda(declaration) => {
+                write!(f, "(fun")?;
+                for param in &declaration.params {
+                    write!(f, " {}", param.lexeme)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -58,13 +662,7 @@ impl fmt::Display for Expr<'_> {
 impl fmt::Display for Literal<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Number(number) => {
-                if number.fract() == 0.0 {
-                    write!(f, "{number:.1}")
-                } else {
-                    write!(f, "{number}")
-                }
-            }
+            Self::Number(number) => write!(f, "{}", format_literal_number(*number)),
             Self::String(string) => write!(f, "{string}"),
             Self::Boolean(bool) => write!(f, "{bool}"),
             Self::Nil => write!(f, "nil"),
@@ -77,15 +675,22 @@ impl fmt::Display for Operator {
         let s = match self {
             Self::Add => "+",
             Self::Subtract => "-",
+            Self::Multiply => "*",
             Self::Divide => "/",
+            Self::Modulo => "%",
+            Self::Power => "**",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::Not => "!",
             Self::GreaterThan => ">",
             Self::LessThan => "<",
             Self::GreaterThanEqual => ">=",
             Self::LessThanEqual => "<=",
             Self::EqualEqual => "==",
             Self::NotEqual => "!=",
-            Self::And => "&",
-            Self::Or => "|",
+            Self::And => "and",
+            Self::Or => "or",
         };
         write!(f, "{s}")
     }
@@ -0,0 +1,1744 @@
+use crate::{
+    environment::{Environment, EnvironmentRef},
+    errors::InterpreterError,
+    first_error,
+    grammar::{Expr, ExprId, Operator, Statement},
+    lexer::Lexer,
+    natives,
+    parser::Parser,
+    resolver::Resolver,
+    token::Token,
+    value::{Arity, Class, Function, Instance, List, LiteralValue, Module, format_number},
+};
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+use thiserror::Error;
+
+/// Called with the variable name and its new value.
+pub type AssignHook<'a> = Box<dyn FnMut(&str, &LiteralValue<'a>) + 'a>;
+
+pub struct Interpreter<'a> {
+    globals: EnvironmentRef<'a>,
+    environment: EnvironmentRef<'a>,
+    locals: HashMap<ExprId, usize>,
+    writer: Box<dyn Write + 'a>,
+    strict_equality: bool,
+    equality_tolerance: f64,
+    lenient_indexing: bool,
+    lenient_undefined: bool,
+    truncation_warnings: bool,
+    warnings: Vec<String>,
+    output_limit: Option<usize>,
+    bytes_written: usize,
+    string_limit: Option<usize>,
+    line_buffered: bool,
+    echo: bool,
+    iterative_evaluation: bool,
+    interactive_breakpoints: bool,
+    on_assign: Option<AssignHook<'a>>,
+    disabled_natives: HashSet<String>,
+    script_path: Option<PathBuf>,
+    imported: HashSet<PathBuf>,
+    modules: HashMap<PathBuf, Rc<Module<'a>>>,
+}
+
+impl fmt::Debug for Interpreter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interpreter")
+            .field("globals", &self.globals)
+            .field("environment", &self.environment)
+            .field("locals", &self.locals)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Interpreter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new() -> Self {
+        Self::with_writer(io::stdout())
+    }
+
+    /// Sends `print` output to `writer` instead of stdout.
+    pub fn with_writer(writer: impl Write + 'a) -> Self {
+        let globals = Environment::new();
+        natives::install(&mut globals.borrow_mut());
+
+        Self {
+            environment: Rc::clone(&globals),
+            globals,
+            locals: HashMap::new(),
+            writer: Box::new(writer),
+            strict_equality: false,
+            equality_tolerance: 0.0,
+            lenient_indexing: false,
+            lenient_undefined: false,
+            truncation_warnings: false,
+            warnings: Vec::new(),
+            output_limit: None,
+            bytes_written: 0,
+            string_limit: None,
+            line_buffered: false,
+            echo: false,
+            iterative_evaluation: false,
+            interactive_breakpoints: false,
+            on_assign: None,
+            disabled_natives: HashSet::new(),
+            script_path: None,
+            imported: HashSet::new(),
+            modules: HashMap::new(),
+        }
+    }
+
+    /// Resolves `import` paths against the directory of the script at
+    /// `path` instead of the working directory. The script itself counts as
+    /// imported, so a file importing it back does nothing.
+    pub fn with_script_path(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if let Ok(canonical) = fs::canonicalize(&path) {
+            self.imported.insert(canonical);
+        }
+        self.script_path = Some(path);
+        self
+    }
+
+    /// Makes `==` and `!=` between values of different types a runtime
+    /// error instead of evaluating to unequal.
+    pub const fn with_strict_equality(mut self, enabled: bool) -> Self {
+        self.strict_equality = enabled;
+        self
+    }
+
+    /// Makes `==` and `!=` treat numbers within `tolerance` of each other as
+    /// equal, so `0.1 + 0.2 == 0.3` can hold. The default of 0 is exact.
+    pub const fn with_equality_tolerance(mut self, tolerance: f64) -> Self {
+        self.equality_tolerance = tolerance;
+        self
+    }
+
+    /// Makes reading a list past its bounds evaluate to `nil` instead of
+    /// raising a runtime error. Out-of-bounds assignment still fails.
+    pub const fn with_lenient_indexing(mut self, enabled: bool) -> Self {
+        self.lenient_indexing = enabled;
+        self
+    }
+
+    /// Makes reading an undefined variable evaluate to `nil`, and assigning
+    /// to one define it as a global, instead of raising a runtime error.
+    pub const fn with_lenient_undefined(mut self, enabled: bool) -> Self {
+        self.lenient_undefined = enabled;
+        self
+    }
+
+    /// Truncates fractional list indices and bitwise operands instead of
+    /// rejecting them, recording a warning for each one in `warnings`.
+    pub const fn with_truncation_warnings(mut self, enabled: bool) -> Self {
+        self.truncation_warnings = enabled;
+        self
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Caps the total bytes written by `print` and `printf`; a write that
+    /// would go past `bytes` fails with "Output limit exceeded." instead.
+    pub const fn with_output_limit(mut self, bytes: usize) -> Self {
+        self.output_limit = Some(bytes);
+        self
+    }
+
+    /// Caps the length, in characters as `len()` counts them, of a string
+    /// built by `+`. Longer results fail with "String length limit exceeded."
+    pub const fn with_string_limit(mut self, chars: usize) -> Self {
+        self.string_limit = Some(chars);
+        self
+    }
+
+    /// Flushes the writer after every `print`, so output interleaves
+    /// predictably with stderr when piped. Off by default.
+    pub const fn with_line_buffering(mut self, enabled: bool) -> Self {
+        self.line_buffered = enabled;
+        self
+    }
+
+    /// Prints the value of every expression statement, so `1 + 1;` shows
+    /// `2` without a `print`. Meant for the REPL and teaching; off for `run`.
+    pub const fn with_echo(mut self, enabled: bool) -> Self {
+        self.echo = enabled;
+        self
+    }
+
+    /// Evaluates operator chains with an explicit work stack instead of Rust
+    /// recursion, so a deeply nested expression like `1 - 1 - ... - 1` can't
+    /// overflow the stack. Calls, assignments and other expressions still
+    /// recurse once per level. Off by default.
+    pub const fn with_iterative_evaluation(mut self, enabled: bool) -> Self {
+        self.iterative_evaluation = enabled;
+        self
+    }
+
+    /// Makes a triggered `breakpoint()` wait for a line on stdin after
+    /// logging the scope. Off by default, so breakpoints only log.
+    pub const fn with_interactive_breakpoints(mut self, enabled: bool) -> Self {
+        self.interactive_breakpoints = enabled;
+        self
+    }
+
+    pub(crate) const fn interactive_breakpoints(&self) -> bool {
+        self.interactive_breakpoints
+    }
+
+    pub(crate) const fn environment(&self) -> &EnvironmentRef<'a> {
+        &self.environment
+    }
+
+    /// Registers `hook` to run whenever a variable is defined with `var` or
+    /// `const`, assigned, or incremented. Replaces any previous hook.
+    pub fn on_assign(&mut self, hook: AssignHook<'a>) {
+        self.on_assign = Some(hook);
+    }
+
+    /// Makes calls to the native `name` fail with "Function 'name' is
+    /// disabled.", including calls through another variable holding it.
+    pub fn disable_native(&mut self, name: &str) {
+        self.disabled_natives.insert(name.to_string());
+    }
+
+    fn notify_assign(&mut self, name: &str, value: &LiteralValue<'a>) {
+        if let Some(hook) = &mut self.on_assign {
+            hook(name, value);
+        }
+    }
+
+    pub(crate) fn write_output(&mut self, text: &str) -> Result<(), RuntimeErrorKind> {
+        let total = self.bytes_written + text.len();
+        if self.output_limit.is_some_and(|limit| total > limit) {
+            return Err(RuntimeErrorKind::OutputLimitExceeded);
+        }
+
+        self.writer.write_all(text.as_bytes())?;
+        self.bytes_written = total;
+
+        if self.line_buffered {
+            self.writer.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn writer(&mut self) -> &mut dyn Write {
+        &mut self.writer
+    }
+
+    pub fn resolve(&mut self, id: ExprId, depth: usize) {
+        self.locals.insert(id, depth);
+    }
+
+    pub fn run(&mut self, statements: &[Statement<'a>]) -> Result<(), RuntimeError> {
+        match self.execute_all(statements) {
+            Ok(()) | Err(Signal::Return(_) | Signal::Break | Signal::Continue) => Ok(()),
+            Err(Signal::Error(e)) => Err(e),
+        }
+    }
+
+    /// Parses, resolves and runs a token stream built without the lexer.
+    /// Only the first parse or resolve error is reported.
+    pub fn run_tokens(&mut self, tokens: Vec<Token<'a>>) -> Result<(), InterpreterError> {
+        let statements = Parser::from_tokens(&tokens)?
+            .parse_statements()
+            .map_err(first_error)?;
+
+        Resolver::new(self)
+            .resolve(&statements)
+            .map_err(first_error)?;
+
+        Ok(self.run(&statements)?)
+    }
+
+    /// Runs `statements` with output captured instead of written to the
+    /// writer, returning one entry per printed line.
+    pub fn run_collecting(
+        &mut self,
+        statements: &[Statement<'a>],
+    ) -> Result<Vec<String>, RuntimeError> {
+        let buffer = SharedBuffer::default();
+        let writer = std::mem::replace(&mut self.writer, Box::new(buffer.clone()));
+        let result = self.run(statements);
+        self.writer = writer;
+        result?;
+
+        let output = buffer.0.take();
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Evaluates each expression against the same environment, keeping
+    /// going after a failure so every expression gets its own result.
+    pub fn eval_many(
+        &mut self,
+        exprs: Vec<Expr<'a>>,
+    ) -> Vec<Result<LiteralValue<'a>, RuntimeError>> {
+        exprs.iter().map(|expr| self.evaluate(expr)).collect()
+    }
+
+    /// Function declarations are hoisted: every one in `statements` is
+    /// defined before the first statement runs, so code can call a function
+    /// declared further down the same scope.
+    fn execute_all(&mut self, statements: &[Statement<'a>]) -> Result<(), Signal<'a>> {
+        for statement in statements {
+            if let Statement::Function(declaration) = statement {
+                let function = Function {
+                    declaration: Rc::clone(declaration),
+                    closure: Rc::clone(&self.environment),
+                    is_initializer: false,
+                };
+                self.environment.borrow_mut().define(
+                    declaration.name.lexeme,
+                    LiteralValue::Function(Rc::new(function)),
+                );
+            }
+        }
+
+        for statement in statements {
+            self.execute(statement)?;
+        }
+
+        Ok(())
+    }
+
+    fn execute(&mut self, statement: &Statement<'a>) -> Result<(), Signal<'a>> {
+        match statement {
+            Statement::Expression(expr) => {
+                let value = self.evaluate(expr)?;
+
+                if self.echo {
+                    self.write_output(&format!("{value}\n"))
+                        .map_err(|kind| RuntimeError::new(kind, expr.line().unwrap_or_default()))?;
+                }
+            }
+            Statement::Print { keyword, values } => {
+                let values = values
+                    .iter()
+                    .map(|value| self.evaluate(value).map(|value| value.as_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.write_output(&format!("{}\n", values.join(" ")))
+                    .map_err(|kind| RuntimeError::new(kind, keyword.line))?;
+            }
+            Statement::Var { name, initializer } => {
+                let value = match initializer {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => LiteralValue::Nil,
+                };
+                self.notify_assign(name.lexeme, &value);
+                self.environment.borrow_mut().define(name.lexeme, value);
+            }
+            Statement::Const { name, initializer } => {
+                let value = self.evaluate(initializer)?;
+                self.notify_assign(name.lexeme, &value);
+                self.environment
+                    .borrow_mut()
+                    .define_const(name.lexeme, value);
+            }
+            Statement::Block(statements) => {
+                let environment = Environment::with_enclosing(Rc::clone(&self.environment));
+                self.execute_block(statements, environment)?;
+            }
+            // Already defined when the enclosing scope started running.
+            Statement::Function(_) => {}
+            // Checked by the resolver before anything runs.
+            Statement::StaticAssert { .. } => {}
+            Statement::Class {
+                name,
+                superclass,
+                methods,
+            } => {
+                let superclass = match superclass {
+                    Some(superclass) => match self.evaluate(superclass)? {
+                        LiteralValue::Class(class) => Some(class),
+                        _ => {
+                            return Err(RuntimeError::new(
+                                RuntimeErrorKind::SuperclassMustBeClass,
+                                superclass.line().unwrap_or(name.line),
+                            )
+                            .into());
+                        }
+                    },
+                    None => None,
+                };
+
+                let closure = match &superclass {
+                    Some(superclass) => {
+                        let environment = Environment::with_enclosing(Rc::clone(&self.environment));
+                        environment
+                            .borrow_mut()
+                            .define("super", LiteralValue::Class(Rc::clone(superclass)));
+                        environment
+                    }
+                    None => Rc::clone(&self.environment),
+                };
+
+                let methods = methods
+                    .iter()
+                    .map(|declaration| {
+                        let method = Function {
+                            declaration: Rc::clone(declaration),
+                            closure: Rc::clone(&closure),
+                            is_initializer: declaration.name.lexeme == "init",
+                        };
+                        (declaration.name.lexeme, Rc::new(method))
+                    })
+                    .collect();
+
+                let class = Class {
+                    name: name.lexeme,
+                    superclass,
+                    methods,
+                };
+                self.environment
+                    .borrow_mut()
+                    .define(name.lexeme, LiteralValue::Class(Rc::new(class)));
+            }
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.evaluate(condition)?.is_truthy() {
+                    self.execute(then_branch)?;
+                } else if let Some(else_branch) = else_branch {
+                    self.execute(else_branch)?;
+                }
+            }
+            Statement::While {
+                condition,
+                body,
+                increment,
+            } => {
+                while self.evaluate(condition)?.is_truthy() {
+                    match self.execute(body) {
+                        Ok(()) | Err(Signal::Continue) => {}
+                        Err(Signal::Break) => break,
+                        Err(signal) => return Err(signal),
+                    }
+
+                    if let Some(increment) = increment {
+                        self.evaluate(increment)?;
+                    }
+                }
+            }
+            Statement::Import {
+                keyword,
+                path,
+                alias,
+            } => self.import(path, alias.as_ref(), keyword.line)?,
+            Statement::Break => return Err(Signal::Break),
+            Statement::Continue => return Err(Signal::Continue),
+            Statement::Return { value, .. } => {
+                let value = match value {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => LiteralValue::Nil,
+                };
+                return Err(Signal::Return(value));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the file at `path` into the global scope, or with an `alias`,
+    /// into its own globals bound to the alias as a module. Each file runs at
+    /// most once per form, so importing it again, directly or through a
+    /// cycle, does nothing or rebinds the same module.
+    fn import(
+        &mut self,
+        path: &str,
+        alias: Option<&Token<'a>>,
+        line: usize,
+    ) -> Result<(), Signal<'a>> {
+        let import_error = |message: String| {
+            RuntimeError::new(RuntimeErrorKind::Import(path.to_string(), message), line)
+        };
+
+        let base = self
+            .script_path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new(""));
+        let resolved =
+            fs::canonicalize(base.join(path)).map_err(|e| import_error(e.to_string()))?;
+
+        let Some(alias) = alias else {
+            if !self.imported.insert(resolved.clone()) {
+                return Ok(());
+            }
+
+            let statements = self.load(&resolved, import_error)?;
+            return self.run_module(&statements, Rc::clone(&self.globals), resolved);
+        };
+
+        let module = if let Some(module) = self.modules.get(&resolved) {
+            Rc::clone(module)
+        } else {
+            let globals = Environment::new();
+            natives::install(&mut globals.borrow_mut());
+            let module = Rc::new(Module {
+                path: path.to_string(),
+                globals: Rc::clone(&globals),
+            });
+            // Registered before running, so a cycle back to this file binds
+            // the module as far as it has got instead of running it again.
+            self.modules.insert(resolved.clone(), Rc::clone(&module));
+
+            let statements = self.load(&resolved, import_error)?;
+            self.run_module(&statements, globals, resolved)?;
+            module
+        };
+
+        self.environment
+            .borrow_mut()
+            .define(alias.lexeme, LiteralValue::Module(module));
+        Ok(())
+    }
+
+    /// Reads, parses and resolves an imported file.
+    fn load(
+        &mut self,
+        path: &Path,
+        import_error: impl Fn(String) -> RuntimeError,
+    ) -> Result<Vec<Statement<'a>>, RuntimeError> {
+        let source = fs::read_to_string(path).map_err(|e| import_error(e.to_string()))?;
+        // Functions and classes keep borrowing the source they were declared
+        // in for as long as the interpreter lives. Each file is only read
+        // once, so this leaks at most one copy of every imported file.
+        let source: &'a str = Box::leak(source.into_boxed_str());
+
+        let result = Lexer::new(source).scan_tokens();
+        if let Some(error) = result.errors.first() {
+            return Err(import_error(error.to_string()));
+        }
+        let statements = Parser::new(&result.tokens)
+            .parse_statements()
+            .map_err(|errors| import_error(errors[0].to_string()))?;
+        Resolver::new(self)
+            .resolve(&statements)
+            .map_err(|errors| import_error(errors[0].to_string()))?;
+
+        Ok(statements)
+    }
+
+    fn run_module(
+        &mut self,
+        statements: &[Statement<'a>],
+        globals: EnvironmentRef<'a>,
+        path: PathBuf,
+    ) -> Result<(), Signal<'a>> {
+        let script_path = self.script_path.replace(path);
+        let environment = std::mem::replace(&mut self.environment, Rc::clone(&globals));
+        let globals = std::mem::replace(&mut self.globals, globals);
+        let result = self.execute_all(statements);
+        self.globals = globals;
+        self.environment = environment;
+        self.script_path = script_path;
+
+        result
+    }
+
+    fn execute_block(
+        &mut self,
+        statements: &[Statement<'a>],
+        environment: EnvironmentRef<'a>,
+    ) -> Result<(), Signal<'a>> {
+        let previous = std::mem::replace(&mut self.environment, environment);
+        let result = self.execute_all(statements);
+        self.environment = previous;
+
+        result
+    }
+
+    pub fn evaluate(&mut self, expr: &Expr<'a>) -> Result<LiteralValue<'a>, RuntimeError> {
+        if self.iterative_evaluation {
+            self.evaluate_iterative(expr)
+        } else {
+            self.evaluate_recursive(expr)
+        }
+    }
+
+    /// Walks groupings, unary, binary, logical and ternary expressions with
+    /// a stack of pending tasks and a stack of operand values. Any other
+    /// expression is a leaf here and goes through `evaluate_recursive`.
+    fn evaluate_iterative(&mut self, expr: &Expr<'a>) -> Result<LiteralValue<'a>, RuntimeError> {
+        let mut tasks = vec![Task::Evaluate(expr)];
+        let mut values = Vec::new();
+
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Evaluate(expr) => match expr {
+                    Expr::Grouping(expr) => tasks.push(Task::Evaluate(expr)),
+                    Expr::Unary {
+                        operator,
+                        operand,
+                        line,
+                    } => {
+                        tasks.push(Task::Unary(*operator, *line));
+                        tasks.push(Task::Evaluate(operand));
+                    }
+                    Expr::Binary {
+                        left_operand,
+                        operator,
+                        right_operand,
+                        line,
+                    } => {
+                        tasks.push(Task::Binary(*operator, *line));
+                        tasks.push(Task::Evaluate(right_operand));
+                        tasks.push(Task::Evaluate(left_operand));
+                    }
+                    Expr::Logical {
+                        left_operand,
+                        operator,
+                        right_operand,
+                    } => {
+                        tasks.push(Task::Logical(*operator, right_operand));
+                        tasks.push(Task::Evaluate(left_operand));
+                    }
+                    Expr::Ternary {
+                        condition,
+                        then_branch,
+                        else_branch,
+                    } => {
+                        tasks.push(Task::Ternary(then_branch, else_branch));
+                        tasks.push(Task::Evaluate(condition));
+                    }
+                    _ => values.push(self.evaluate_recursive(expr)?),
+                },
+                // A task's operands were pushed by the tasks scheduled after
+                // it, so they are on top of `values` when it runs.
+                Task::Unary(operator, line) => {
+                    let operand = values.pop().unwrap_or(LiteralValue::Nil);
+                    values.push(unary(operator, &operand, line)?);
+                }
+                Task::Binary(operator, line) => {
+                    let right = values.pop().unwrap_or(LiteralValue::Nil);
+                    let left = values.pop().unwrap_or(LiteralValue::Nil);
+                    values.push(self.binary(operator, &left, &right, line)?);
+                }
+                Task::Logical(operator, right_operand) => {
+                    let left = values.pop().unwrap_or(LiteralValue::Nil);
+                    if short_circuits(operator, &left) {
+                        values.push(left);
+                    } else {
+                        tasks.push(Task::Evaluate(right_operand));
+                    }
+                }
+                Task::Ternary(then_branch, else_branch) => {
+                    let condition = values.pop().unwrap_or(LiteralValue::Nil);
+                    tasks.push(Task::Evaluate(if condition.is_truthy() {
+                        then_branch
+                    } else {
+                        else_branch
+                    }));
+                }
+            }
+        }
+
+        Ok(values.pop().unwrap_or(LiteralValue::Nil))
+    }
+
+    fn evaluate_recursive(&mut self, expr: &Expr<'a>) -> Result<LiteralValue<'a>, RuntimeError> {
+        match expr {
+            Expr::Literal(literal) => Ok(literal.into()),
+            Expr::Grouping(expr) => self.evaluate(expr),
+            Expr::Unary {
+                operator,
+                operand,
+                line,
+            } => {
+                let operand = self.evaluate(operand)?;
+                unary(*operator, &operand, *line)
+            }
+            Expr::Binary {
+                left_operand,
+                operator,
+                right_operand,
+                line,
+            } => {
+                let left = self.evaluate(left_operand)?;
+                let right = self.evaluate(right_operand)?;
+
+                self.binary(*operator, &left, &right, *line)
+            }
+            Expr::Logical {
+                left_operand,
+                operator,
+                right_operand,
+            } => {
+                let left = self.evaluate(left_operand)?;
+
+                if short_circuits(*operator, &left) {
+                    Ok(left)
+                } else {
+                    self.evaluate(right_operand)
+                }
+            }
+            Expr::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if self.evaluate(condition)?.is_truthy() {
+                    self.evaluate(then_branch)
+                } else {
+                    self.evaluate(else_branch)
+                }
+            }
+            Expr::Variable { id, name } | Expr::This { id, keyword: name } => {
+                self.look_up_variable(*id, name)
+            }
+            Expr::Assignment { id, name, value } => {
+                let value = self.evaluate(value)?;
+                self.assign_variable(*id, name, &value)?;
+
+                Ok(value)
+            }
+            Expr::Postfix { id, name, operator } => {
+                let old = self.look_up_variable(*id, name)?;
+                let number = expect_number(&old, name.line)?;
+                let new = if *operator == Operator::Add {
+                    number + 1.0
+                } else {
+                    number - 1.0
+                };
+                self.assign_variable(*id, name, &LiteralValue::Number(new))?;
+
+                Ok(old)
+            }
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                let callee = self.evaluate(callee)?;
+
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match callee {
+                    LiteralValue::Function(function) => {
+                        check_arity(Arity::Exact(function.arity()), arguments.len(), paren)?;
+                        self.call(&function, arguments)
+                    }
+                    LiteralValue::Class(class) => {
+                        let initializer = class.find_method("init");
+                        let arity = initializer.as_ref().map_or(0, |init| init.arity());
+                        check_arity(Arity::Exact(arity), arguments.len(), paren)?;
+
+                        let instance = Rc::new(Instance::new(class));
+                        if let Some(initializer) = initializer {
+                            self.call(&initializer.bind(Rc::clone(&instance)), arguments)?;
+                        }
+
+                        Ok(LiteralValue::Instance(instance))
+                    }
+                    LiteralValue::NativeFunction(native) => {
+                        if self.disabled_natives.contains(native.name) {
+                            return Err(RuntimeError::new(
+                                RuntimeErrorKind::NativeDisabled(native.name),
+                                paren.line,
+                            ));
+                        }
+
+                        check_arity(native.arity, arguments.len(), paren)?;
+                        (native.function)(self, &arguments)
+                            .map_err(|kind| RuntimeError::new(kind, paren.line))
+                    }
+                    _ => Err(RuntimeError::new(RuntimeErrorKind::NotCallable, paren.line)),
+                }
+            }
+            Expr::Super {
+                id,
+                keyword,
+                method,
+            } => {
+                let distance = self.locals.get(id).copied().unwrap_or_default();
+                let superclass = Environment::get_at(&self.environment, distance, keyword)?;
+                let this = Environment::ancestor(&self.environment, distance.saturating_sub(1))
+                    .borrow()
+                    .get_own("this");
+
+                let (LiteralValue::Class(superclass), Some(LiteralValue::Instance(instance))) =
+                    (superclass, this)
+                else {
+                    return Err(RuntimeError::new(
+                        RuntimeErrorKind::SuperclassMustBeClass,
+                        keyword.line,
+                    ));
+                };
+
+                superclass
+                    .find_method(method.lexeme)
+                    .map(|found| LiteralValue::Function(Rc::new(found.bind(instance))))
+                    .ok_or_else(|| {
+                        RuntimeError::new(
+                            RuntimeErrorKind::UndefinedProperty(method.lexeme.to_string()),
+                            method.line,
+                        )
+                    })
+            }
+            Expr::Get { object, name } => match self.evaluate(object)? {
+                LiteralValue::Instance(instance) => instance.get(name),
+                LiteralValue::Module(module) => module.get(name),
+                _ => Err(RuntimeError::new(
+                    RuntimeErrorKind::OnlyInstancesHaveProperties,
+                    name.line,
+                )),
+            },
+            Expr::Set {
+                object,
+                name,
+                value,
+            } => {
+                let LiteralValue::Instance(instance) = self.evaluate(object)? else {
+                    return Err(RuntimeError::new(
+                        RuntimeErrorKind::OnlyInstancesHaveFields,
+                        name.line,
+                    ));
+                };
+
+                if instance.is_frozen() {
+                    return Err(RuntimeError::new(RuntimeErrorKind::FrozenValue, name.line));
+                }
+
+                let value = self.evaluate(value)?;
+                instance.set(name, value.clone());
+
+                Ok(value)
+            }
+            Expr::ListLiteral(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.evaluate(element))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(LiteralValue::List(Rc::new(List::new(elements))))
+            }
+            Expr::Lambda(declaration) => Ok(LiteralValue::Function(Rc::new(Function {
+                declaration: Rc::clone(declaration),
+                closure: Rc::clone(&self.environment),
+                is_initializer: false,
+            }))),
+            Expr::Comma(expressions) => {
+                let mut value = LiteralValue::Nil;
+                for expr in expressions {
+                    value = self.evaluate(expr)?;
+                }
+
+                Ok(value)
+            }
+            Expr::Index {
+                target,
+                bracket,
+                index,
+            } => {
+                let target = self.evaluate(target)?;
+                let index = self.evaluate(index)?;
+                let index = self.truncate_index(index);
+
+                let list = expect_list(&target, bracket.line)?.elements.borrow();
+                match list_index(&index, list.len(), bracket.line) {
+                    Ok(index) => Ok(list[index].clone()),
+                    Err(RuntimeError {
+                        kind: RuntimeErrorKind::IndexOutOfBounds,
+                        ..
+                    }) if self.lenient_indexing => Ok(LiteralValue::Nil),
+                    Err(error) => Err(error),
+                }
+            }
+            Expr::IndexAssignment {
+                target,
+                bracket,
+                index,
+                value,
+            } => {
+                let target = self.evaluate(target)?;
+                let index = self.evaluate(index)?;
+                let index = self.truncate_index(index);
+                let value = self.evaluate(value)?;
+
+                let list = expect_list(&target, bracket.line)?;
+                if list.is_frozen() {
+                    return Err(RuntimeError::new(
+                        RuntimeErrorKind::FrozenValue,
+                        bracket.line,
+                    ));
+                }
+
+                let mut list = list.elements.borrow_mut();
+                let index = list_index(&index, list.len(), bracket.line)?;
+                list[index] = value.clone();
+
+                Ok(value)
+            }
+        }
+    }
+
+    fn look_up_variable(
+        &self,
+        id: ExprId,
+        name: &Token<'_>,
+    ) -> Result<LiteralValue<'a>, RuntimeError> {
+        match self.locals.get(&id) {
+            Some(&depth) => Environment::get_at(&self.environment, depth, name),
+            None if self.lenient_undefined => Ok(self
+                .globals
+                .borrow()
+                .get_own(name.lexeme)
+                .unwrap_or(LiteralValue::Nil)),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    fn assign_variable(
+        &mut self,
+        id: ExprId,
+        name: &Token<'_>,
+        value: &LiteralValue<'a>,
+    ) -> Result<(), RuntimeError> {
+        match self.locals.get(&id) {
+            Some(&depth) => {
+                Environment::assign_at(&self.environment, depth, name, value.clone())?;
+            }
+            None if self.lenient_undefined
+                && self.globals.borrow().get_own(name.lexeme).is_none() =>
+            {
+                self.globals.borrow_mut().define(name.lexeme, value.clone());
+            }
+            None => self.globals.borrow_mut().assign(name, value.clone())?,
+        }
+
+        self.notify_assign(name.lexeme, value);
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        function: &Function<'a>,
+        arguments: Vec<LiteralValue<'a>>,
+    ) -> Result<LiteralValue<'a>, RuntimeError> {
+        let environment = Environment::with_enclosing(Rc::clone(&function.closure));
+        for (param, argument) in function.declaration.params.iter().zip(arguments) {
+            environment.borrow_mut().define(param.lexeme, argument);
+        }
+
+        // A function from an imported module reads that module's globals.
+        let globals = std::mem::replace(&mut self.globals, Environment::root(&function.closure));
+        let result = self.execute_block(&function.declaration.body, environment);
+        self.globals = globals;
+
+        let value = match result {
+            Ok(()) | Err(Signal::Break | Signal::Continue) => LiteralValue::Nil,
+            Err(Signal::Return(value)) => value,
+            Err(Signal::Error(e)) => return Err(e),
+        };
+
+        if function.is_initializer {
+            return Ok(function.bound_this());
+        }
+
+        Ok(value)
+    }
+
+    fn binary(
+        &mut self,
+        operator: Operator,
+        left: &LiteralValue<'a>,
+        right: &LiteralValue<'a>,
+        line: usize,
+    ) -> Result<LiteralValue<'a>, RuntimeError> {
+        let value = match operator {
+            Operator::Add => match (left, right) {
+                (LiteralValue::Number(l), LiteralValue::Number(r)) => LiteralValue::Number(l + r),
+                (LiteralValue::String(l), LiteralValue::String(r)) => {
+                    if self
+                        .string_limit
+                        .is_some_and(|limit| l.chars().count() + r.chars().count() > limit)
+                    {
+                        return Err(RuntimeError::new(
+                            RuntimeErrorKind::StringLimitExceeded,
+                            line,
+                        ));
+                    }
+
+                    LiteralValue::String(format!("{l}{r}"))
+                }
+                _ => {
+                    return Err(RuntimeError::new(
+                        RuntimeErrorKind::OperandsMustBeNumbersOrStrings,
+                        line,
+                    ));
+                }
+            },
+            Operator::Subtract => {
+                let (l, r) = expect_numbers(left, right, line)?;
+                LiteralValue::Number(l - r)
+            }
+            Operator::Multiply => {
+                let (l, r) = expect_numbers(left, right, line)?;
+                LiteralValue::Number(l * r)
+            }
+            Operator::Divide => {
+                let (l, r) = expect_numbers(left, right, line)?;
+                if r == 0.0 {
+                    return Err(RuntimeError::new(RuntimeErrorKind::DivisionByZero, line));
+                }
+                LiteralValue::Number(l / r)
+            }
+            Operator::Modulo => {
+                let (l, r) = expect_numbers(left, right, line)?;
+                if r == 0.0 {
+                    return Err(RuntimeError::new(RuntimeErrorKind::DivisionByZero, line));
+                }
+                LiteralValue::Number(l % r)
+            }
+            Operator::Power => {
+                let (l, r) = expect_numbers(left, right, line)?;
+                LiteralValue::Number(l.powf(r))
+            }
+            Operator::BitAnd => {
+                let (l, r) = self.integers(left, right, line)?;
+                integer_value(l & r)
+            }
+            Operator::BitOr => {
+                let (l, r) = self.integers(left, right, line)?;
+                integer_value(l | r)
+            }
+            Operator::BitXor => {
+                let (l, r) = self.integers(left, right, line)?;
+                integer_value(l ^ r)
+            }
+            Operator::GreaterThan => {
+                LiteralValue::Boolean(compare(left, right, line)?.is_some_and(Ordering::is_gt))
+            }
+            Operator::GreaterThanEqual => {
+                LiteralValue::Boolean(compare(left, right, line)?.is_some_and(Ordering::is_ge))
+            }
+            Operator::LessThan => {
+                LiteralValue::Boolean(compare(left, right, line)?.is_some_and(Ordering::is_lt))
+            }
+            Operator::LessThanEqual => {
+                LiteralValue::Boolean(compare(left, right, line)?.is_some_and(Ordering::is_le))
+            }
+            Operator::EqualEqual => LiteralValue::Boolean(self.equal(left, right, line)?),
+            Operator::NotEqual => LiteralValue::Boolean(!self.equal(left, right, line)?),
+            Operator::Not | Operator::And | Operator::Or => {
+                return Err(RuntimeError::new(
+                    RuntimeErrorKind::UnsupportedOperator(operator),
+                    line,
+                ));
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn integers(
+        &mut self,
+        left: &LiteralValue<'a>,
+        right: &LiteralValue<'a>,
+        line: usize,
+    ) -> Result<(i64, i64), RuntimeError> {
+        let (l, r) = expect_numbers(left, right, line)?;
+        expect_integers(
+            self.truncate(l, "Operand"),
+            self.truncate(r, "Operand"),
+            line,
+        )
+    }
+
+    fn truncate_index(&mut self, index: LiteralValue<'a>) -> LiteralValue<'a> {
+        match index {
+            LiteralValue::Number(n) => LiteralValue::Number(self.truncate(n, "Index")),
+            index => index,
+        }
+    }
+
+    /// Leaves `n` alone unless truncation warnings are on, so the caller
+    /// still rejects fractional values by default.
+    fn truncate(&mut self, n: f64, what: &str) -> f64 {
+        if !self.truncation_warnings || !n.is_finite() || n.fract() == 0.0 {
+            return n;
+        }
+
+        let truncated = n.trunc();
+        self.warnings.push(format!(
+            "{what} {} truncated to {}.",
+            format_number(n),
+            format_number(truncated)
+        ));
+
+        truncated
+    }
+
+    fn equal(
+        &self,
+        left: &LiteralValue<'a>,
+        right: &LiteralValue<'a>,
+        line: usize,
+    ) -> Result<bool, RuntimeError> {
+        if self.strict_equality && left.type_name() != right.type_name() {
+            return Err(RuntimeError::new(
+                RuntimeErrorKind::IncomparableTypes(left.type_name(), right.type_name()),
+                line,
+            ));
+        }
+
+        Ok(left.is_equal_within(right, self.equality_tolerance))
+    }
+}
+
+fn check_arity(arity: Arity, got: usize, paren: &Token<'_>) -> Result<(), RuntimeError> {
+    let kind = match arity {
+        Arity::Exact(expected) if expected != got => {
+            RuntimeErrorKind::ArityMismatch { expected, got }
+        }
+        Arity::AtLeast(min) if got < min => RuntimeErrorKind::TooFewArguments { min, got },
+        Arity::Between(min, max) if !(min..=max).contains(&got) => {
+            RuntimeErrorKind::ArityOutOfRange { min, max, got }
+        }
+        _ => return Ok(()),
+    };
+
+    Err(RuntimeError::new(kind, paren.line))
+}
+
+fn expect_number(operand: &LiteralValue<'_>, line: usize) -> Result<f64, RuntimeError> {
+    match operand {
+        LiteralValue::Number(n) => Ok(*n),
+        _ => Err(RuntimeError::new(
+            RuntimeErrorKind::OperandMustBeNumber,
+            line,
+        )),
+    }
+}
+
+fn expect_numbers(
+    left: &LiteralValue<'_>,
+    right: &LiteralValue<'_>,
+    line: usize,
+) -> Result<(f64, f64), RuntimeError> {
+    match (left, right) {
+        (LiteralValue::Number(l), LiteralValue::Number(r)) => Ok((*l, *r)),
+        _ => Err(RuntimeError::new(
+            RuntimeErrorKind::OperandsMustBeNumbers,
+            line,
+        )),
+    }
+}
+
+/// Both operands as `i64`. Whole numbers outside its range are rejected
+/// too, rather than saturating to `i64::MIN` or `i64::MAX`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn expect_integers(l: f64, r: f64, line: usize) -> Result<(i64, i64), RuntimeError> {
+    // -2^63 converts exactly, so this is the whole range of `i64`.
+    let range = i64::MIN as f64..-(i64::MIN as f64);
+    let is_integer = |n: f64| n.fract() == 0.0 && range.contains(&n);
+
+    if !is_integer(l) || !is_integer(r) {
+        return Err(RuntimeError::new(
+            RuntimeErrorKind::OperandsMustBeIntegers,
+            line,
+        ));
+    }
+
+    Ok((l as i64, r as i64))
+}
+
+/// A pending step of `Interpreter::evaluate_iterative`.
+enum Task<'e, 'a> {
+    Evaluate(&'e Expr<'a>),
+    Unary(Operator, usize),
+    Binary(Operator, usize),
+    Logical(Operator, &'e Expr<'a>),
+    Ternary(&'e Expr<'a>, &'e Expr<'a>),
+}
+
+fn unary<'a>(
+    operator: Operator,
+    operand: &LiteralValue<'a>,
+    line: usize,
+) -> Result<LiteralValue<'a>, RuntimeError> {
+    match operator {
+        Operator::Subtract => Ok(LiteralValue::Number(-expect_number(operand, line)?)),
+        Operator::Not => Ok(LiteralValue::Boolean(!operand.is_truthy())),
+        _ => Err(RuntimeError::new(
+            RuntimeErrorKind::UnsupportedOperator(operator),
+            line,
+        )),
+    }
+}
+
+/// Whether `left` alone decides an `and` or `or`.
+const fn short_circuits(operator: Operator, left: &LiteralValue<'_>) -> bool {
+    match operator {
+        Operator::Or => left.is_truthy(),
+        _ => !left.is_truthy(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+const fn integer_value<'a>(n: i64) -> LiteralValue<'a> {
+    LiteralValue::Number(n as f64)
+}
+
+fn expect_list<'v, 'a>(
+    value: &'v LiteralValue<'a>,
+    line: usize,
+) -> Result<&'v List<'a>, RuntimeError> {
+    match value {
+        LiteralValue::List(list) => Ok(list),
+        _ => Err(RuntimeError::new(RuntimeErrorKind::NotIndexable, line)),
+    }
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn list_index(index: &LiteralValue<'_>, len: usize, line: usize) -> Result<usize, RuntimeError> {
+    let LiteralValue::Number(index) = *index else {
+        return Err(RuntimeError::new(RuntimeErrorKind::IndexMustBeNumber, line));
+    };
+
+    if index.fract() != 0.0 || index < 0.0 || index >= len as f64 {
+        return Err(RuntimeError::new(RuntimeErrorKind::IndexOutOfBounds, line));
+    }
+
+    Ok(index as usize)
+}
+
+fn compare(
+    left: &LiteralValue<'_>,
+    right: &LiteralValue<'_>,
+    line: usize,
+) -> Result<Option<Ordering>, RuntimeError> {
+    match (left, right) {
+        (LiteralValue::Number(l), LiteralValue::Number(r)) => Ok(l.partial_cmp(r)),
+        (LiteralValue::String(l), LiteralValue::String(r)) => Ok(Some(l.cmp(r))),
+        _ => Err(RuntimeError::new(
+            RuntimeErrorKind::OperandsMustBeComparable,
+            line,
+        )),
+    }
+}
+
+/// A writer whose contents stay readable through a clone after it has been
+/// handed to an interpreter.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedBuffer(pub(crate) Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Signal<'a> {
+    Error(RuntimeError),
+    Return(LiteralValue<'a>),
+    Break,
+    Continue,
+}
+
+impl From<RuntimeError> for Signal<'_> {
+    fn from(error: RuntimeError) -> Self {
+        Self::Error(error)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("{kind}\n[line {line}]")]
+pub struct RuntimeError {
+    pub line: usize,
+    pub kind: RuntimeErrorKind,
+}
+
+impl RuntimeError {
+    pub const fn new(kind: RuntimeErrorKind, line: usize) -> Self {
+        Self { line, kind }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RuntimeErrorKind {
+    #[error("Operand must be a number.")]
+    OperandMustBeNumber,
+    #[error("Operands must be numbers.")]
+    OperandsMustBeNumbers,
+    #[error("Operands must be integers.")]
+    OperandsMustBeIntegers,
+    #[error("Operands must be two numbers or two strings.")]
+    OperandsMustBeNumbersOrStrings,
+    #[error("Operands must be numbers or two strings.")]
+    OperandsMustBeComparable,
+    #[error("Cannot compare {0} with {1}.")]
+    IncomparableTypes(&'static str, &'static str),
+    #[error("Division by zero.")]
+    DivisionByZero,
+    #[error("Undefined variable '{0}'.")]
+    UndefinedVariable(String),
+    #[error("Cannot assign to constant '{0}'.")]
+    AssignToConstant(String),
+    #[error("Can only call functions and classes.")]
+    NotCallable,
+    #[error("Only instances have properties.")]
+    OnlyInstancesHaveProperties,
+    #[error("Only instances have fields.")]
+    OnlyInstancesHaveFields,
+    #[error("Cannot mutate frozen value.")]
+    FrozenValue,
+    #[error("Superclass must be a class.")]
+    SuperclassMustBeClass,
+    #[error("Undefined property '{0}'.")]
+    UndefinedProperty(String),
+    #[error("Cannot convert to number.")]
+    NotConvertibleToNumber,
+    #[error("String index out of bounds.")]
+    StringIndexOutOfBounds,
+    #[error("Only lists can be indexed.")]
+    NotIndexable,
+    #[error("List index must be a number.")]
+    IndexMustBeNumber,
+    #[error("Index out of bounds.")]
+    IndexOutOfBounds,
+    #[error("Expected {expected} arguments but got {got}.")]
+    ArityMismatch { expected: usize, got: usize },
+    #[error("Expected at least {min} arguments but got {got}.")]
+    TooFewArguments { min: usize, got: usize },
+    #[error("Expected {min} to {max} arguments but got {got}.")]
+    ArityOutOfRange { min: usize, max: usize, got: usize },
+    #[error("{0}")]
+    AssertionFailed(String),
+    #[error("{name}: {message}")]
+    Native {
+        name: &'static str,
+        message: &'static str,
+    },
+    #[error("Function '{0}' is disabled.")]
+    NativeDisabled(&'static str),
+    #[error("Argument to {0}() must be {1}.")]
+    InvalidArgument(&'static str, &'static str),
+    #[error("Output limit exceeded.")]
+    OutputLimitExceeded,
+    #[error("String length limit exceeded.")]
+    StringLimitExceeded,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Clock error: {0}")]
+    Clock(#[from] std::time::SystemTimeError),
+    #[error("Unsupported operator '{0}'.")]
+    UnsupportedOperator(Operator),
+    #[error("Could not import '{0}': {1}")]
+    Import(String, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser, resolver::Resolver};
+
+    fn run(source: &str) -> Result<String, RuntimeError> {
+        run_with(source, |interpreter| interpreter)
+    }
+
+    fn run_with(
+        source: &str,
+        configure: impl for<'a> FnOnce(Interpreter<'a>) -> Interpreter<'a>,
+    ) -> Result<String, RuntimeError> {
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+        let statements = Parser::new(&tokens).parse_statements().unwrap();
+
+        let output = SharedBuffer::default();
+        let mut interpreter = configure(Interpreter::with_writer(output.clone()));
+        Resolver::new(&mut interpreter)
+            .resolve(&statements)
+            .unwrap();
+        interpreter.run(&statements)?;
+
+        Ok(String::from_utf8_lossy(&output.0.borrow()).into_owned())
+    }
+
+    #[test]
+    fn equality_across_types_is_false_by_default() {
+        assert_eq!(
+            run(r#"print 1 == "1"; print 1 != "1";"#).unwrap(),
+            "false\ntrue\n"
+        );
+    }
+
+    #[test]
+    fn strict_equality_rejects_mixed_types() {
+        let error = run_with(r#"print 1 == "1";"#, |i| i.with_strict_equality(true)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot compare number with string.\n[line 1]"
+        );
+
+        let output = run_with("print 1 == 1; print nil != nil;", |i| {
+            i.with_strict_equality(true)
+        });
+        assert_eq!(output.unwrap(), "true\nfalse\n");
+    }
+
+    #[test]
+    fn out_of_bounds_index_is_an_error_by_default() {
+        let error = run("var a = [1, 2]; print a[99];").unwrap_err();
+        assert_eq!(error.to_string(), "Index out of bounds.\n[line 1]");
+    }
+
+    #[test]
+    fn lenient_indexing_reads_nil_past_the_end() {
+        let output = run_with("var a = [1, 2]; print a[99]; print a[1];", |i| {
+            i.with_lenient_indexing(true)
+        });
+        assert_eq!(output.unwrap(), "nil\n2\n");
+
+        let error = run_with("var a = [1, 2]; a[99] = 3;", |i| {
+            i.with_lenient_indexing(true)
+        });
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn fractional_index_is_an_error_by_default() {
+        let error = run("var a = [1, 2]; print a[1.9];").unwrap_err();
+        assert_eq!(error.to_string(), "Index out of bounds.\n[line 1]");
+    }
+
+    #[test]
+    fn truncation_warnings_collect_each_coercion() {
+        let tokens = Lexer::new("var a = [1, 2]; print a[1.9]; print 6.5 & 3;")
+            .scan_tokens()
+            .tokens;
+        let statements = Parser::new(&tokens).parse_statements().unwrap();
+
+        let output = SharedBuffer::default();
+        let mut interpreter =
+            Interpreter::with_writer(output.clone()).with_truncation_warnings(true);
+        Resolver::new(&mut interpreter)
+            .resolve(&statements)
+            .unwrap();
+        interpreter.run(&statements).unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&output.0.borrow()), "2\n2\n");
+        assert_eq!(
+            interpreter.warnings(),
+            ["Index 1.9 truncated to 1.", "Operand 6.5 truncated to 6."]
+        );
+    }
+
+    #[test]
+    fn output_limit_stops_a_runaway_loop() {
+        let error =
+            run_with(r#"while (true) print "x";"#, |i| i.with_output_limit(10)).unwrap_err();
+        assert_eq!(error.to_string(), "Output limit exceeded.\n[line 1]");
+    }
+
+    #[test]
+    fn output_limit_counts_bytes_across_print_and_printf() {
+        let output = run_with(r#"print "abc"; printf("{}!", 12);"#, |i| {
+            i.with_output_limit(7)
+        });
+        assert_eq!(output.unwrap(), "abc\n12!");
+
+        let error = run_with(r#"print "abc"; printf("{}!", 123);"#, |i| {
+            i.with_output_limit(7)
+        });
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn on_assign_sees_definitions_and_assignments() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&seen);
+
+        let output = run_with("var x = 1; x = 2; { var y; y = x; }", move |mut i| {
+            i.on_assign(Box::new(move |name, value| {
+                recorded.borrow_mut().push(format!("{name}={value}"));
+            }));
+            i
+        });
+
+        assert_eq!(output.unwrap(), "");
+        assert_eq!(*seen.borrow(), ["x=1", "x=2", "y=nil", "y=2"]);
+    }
+
+    #[test]
+    fn run_collecting_returns_each_printed_line() {
+        let tokens = Lexer::new("for (var i = 1; i <= 3; i = i + 1) print i * 10; print \"done\";")
+            .scan_tokens()
+            .tokens;
+        let statements = Parser::new(&tokens).parse_statements().unwrap();
+
+        let output = SharedBuffer::default();
+        let mut interpreter = Interpreter::with_writer(output.clone());
+        Resolver::new(&mut interpreter)
+            .resolve(&statements)
+            .unwrap();
+
+        assert_eq!(
+            interpreter.run_collecting(&statements).unwrap(),
+            ["10", "20", "30", "done"]
+        );
+        assert!(output.0.borrow().is_empty());
+    }
+
+    #[derive(Clone, Default)]
+    struct FlushRecorder(Rc<RefCell<Vec<&'static str>>>);
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().push("write");
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.borrow_mut().push("flush");
+            Ok(())
+        }
+    }
+
+    fn record_flushes(line_buffered: bool) -> Vec<&'static str> {
+        let tokens = Lexer::new("print 1; print 2;").scan_tokens().tokens;
+        let statements = Parser::new(&tokens).parse_statements().unwrap();
+
+        let recorder = FlushRecorder::default();
+        let mut interpreter =
+            Interpreter::with_writer(recorder.clone()).with_line_buffering(line_buffered);
+        interpreter.run(&statements).unwrap();
+
+        recorder.0.take()
+    }
+
+    #[test]
+    fn line_buffering_flushes_after_each_print() {
+        assert_eq!(record_flushes(false), ["write", "write"]);
+        assert_eq!(record_flushes(true), ["write", "flush", "write", "flush"]);
+    }
+
+    #[test]
+    fn undefined_variables_are_an_error_by_default() {
+        let error = run("print missing;").unwrap_err();
+        assert_eq!(error.to_string(), "Undefined variable 'missing'.\n[line 1]");
+
+        assert!(run("missing = 1;").is_err());
+    }
+
+    #[test]
+    fn lenient_undefined_reads_nil_and_assigns_globals() {
+        let output = run_with("print missing; { created = 1; } print created;", |i| {
+            i.with_lenient_undefined(true)
+        });
+        assert_eq!(output.unwrap(), "nil\n1\n");
+    }
+
+    #[test]
+    fn number_equality_is_exact_by_default() {
+        assert_eq!(run("print 0.1 + 0.2 == 0.3;").unwrap(), "false\n");
+    }
+
+    #[test]
+    fn equality_tolerance_absorbs_rounding_error() {
+        let output = run_with("print 0.1 + 0.2 == 0.3; print 1 != 1.1;", |i| {
+            i.with_equality_tolerance(1e-9)
+        });
+        assert_eq!(output.unwrap(), "true\ntrue\n");
+    }
+
+    #[test]
+    fn expression_statements_are_silent_by_default() {
+        assert_eq!(run("1 + 1; var a = 2; a * 3;").unwrap(), "");
+    }
+
+    #[test]
+    fn echo_prints_each_expression_statement() {
+        let output = run_with("1 + 1; var a = 2; a * 3; print a;", |i| i.with_echo(true));
+        assert_eq!(output.unwrap(), "2\n6\n2\n");
+    }
+
+    #[test]
+    fn string_limit_stops_a_doubling_loop() {
+        let source = "var s = \"ab\";\nwhile (true) {\n  s = s + s;\n}";
+        let error = run_with(source, |i| i.with_string_limit(100)).unwrap_err();
+        assert_eq!(error.to_string(), "String length limit exceeded.\n[line 3]");
+
+        let output = run_with(r#"print "é" + "🎉";"#, |i| i.with_string_limit(2));
+        assert_eq!(output.unwrap(), "é🎉\n");
+    }
+
+    #[test]
+    fn eval_many_returns_a_result_per_expression() {
+        let sources = ["1 + 2", "10 / 0", "2 * 3 - 1", r#""a" + "b""#];
+        let tokens = sources.map(|source| Lexer::new(source).scan_tokens().tokens);
+        let exprs = tokens
+            .iter()
+            .map(|tokens| Parser::new(tokens).parse().unwrap())
+            .collect();
+
+        let results = Interpreter::new()
+            .eval_many(exprs)
+            .into_iter()
+            .map(|result| result.map_or_else(|e| e.kind.to_string(), |value| value.to_string()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(results, ["3", "Division by zero.", "5", "ab"]);
+    }
+
+    #[test]
+    fn run_tokens_runs_a_hand_built_stream() {
+        use crate::token::{Literal, TokenKind};
+
+        let number =
+            |lexeme, n| Token::new(TokenKind::Number, lexeme, Some(Literal::Number(n)), 1, 7);
+        let mut tokens = vec![
+            Token::new(TokenKind::Print, "print", None, 1, 1),
+            number("1", 1.0),
+            Token::new(TokenKind::Plus, "+", None, 1, 9),
+            number("2", 2.0),
+            Token::new(TokenKind::Semicolon, ";", None, 1, 12),
+            Token::new(TokenKind::EOF, "", None, 1, 13),
+        ];
+
+        let output = SharedBuffer::default();
+        Interpreter::with_writer(output.clone())
+            .run_tokens(tokens.clone())
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.0.borrow()), "3\n");
+
+        tokens.pop();
+        let error = Interpreter::new().run_tokens(tokens).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "[line 1:12] Error: Token stream must end with EOF."
+        );
+    }
+
+    #[test]
+    fn disabled_natives_fail_when_called() {
+        fn disable_clock(mut interpreter: Interpreter<'_>) -> Interpreter<'_> {
+            interpreter.disable_native("clock");
+            interpreter
+        }
+
+        let error = run_with("print clock();", disable_clock).unwrap_err();
+        assert_eq!(error.to_string(), "Function 'clock' is disabled.\n[line 1]");
+
+        let error = run_with("var c = clock;\nc();", disable_clock).unwrap_err();
+        assert_eq!(error.to_string(), "Function 'clock' is disabled.\n[line 2]");
+
+        assert_eq!(
+            run_with("print len(\"ok\");", disable_clock).unwrap(),
+            "2\n"
+        );
+    }
+
+    #[test]
+    fn writer_can_borrow_a_buffer() {
+        let mut output = Vec::new();
+        let tokens = Lexer::new("print 1 + 1; print \"done\";")
+            .scan_tokens()
+            .tokens;
+        let statements = Parser::new(&tokens).parse_statements().unwrap();
+
+        Interpreter::with_writer(&mut output)
+            .run(&statements)
+            .unwrap();
+
+        assert_eq!(output, b"2\ndone\n");
+    }
+
+    #[test]
+    fn operator_errors_report_the_operator_line() {
+        let error = run("var a = 1;\n\n\n\nprint \"a\" - 1;").unwrap_err();
+        assert_eq!(error.to_string(), "Operands must be numbers.\n[line 5]");
+
+        let error = run("print 1 +\n(-\n\"x\");").unwrap_err();
+        assert_eq!(error.to_string(), "Operand must be a number.\n[line 2]");
+
+        let error = run("var b = true;\nprint 1\n+ b;").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Operands must be two numbers or two strings.\n[line 3]"
+        );
+    }
+
+    #[test]
+    fn superclass_errors_report_the_superclass_line() {
+        let error = run("var A = 1;\nclass B\n<\nA {}").unwrap_err();
+        assert_eq!(error.to_string(), "Superclass must be a class.\n[line 4]");
+    }
+
+    #[test]
+    fn iterative_evaluation_handles_deeply_left_nested_chains() {
+        let source = format!("print 1{};", " - 1".repeat(50_000));
+
+        let output = run_with(&source, |interpreter| {
+            interpreter.with_iterative_evaluation(true)
+        });
+
+        assert_eq!(output.unwrap(), "-49999\n");
+    }
+
+    #[test]
+    fn iterative_evaluation_matches_the_recursive_evaluator() {
+        let programs = [
+            "print 1 + 2 * 3 - -4 / (2 - 4);",
+            r#"print "a" + "b", !nil, !!0, -(-(3));"#,
+            "print nil or 1, false and 1, 0 and 2, nil or false;",
+            "print 1 < 2 ? 1 > 2 ? 3 : 4 : 5;",
+            "var a = 1; var b = a = 2 + 3; print a, b, a == b and a != 1;",
+            "fun f(n) { return n < 2 ? n : f(n - 1) + f(n - 2); } print f(10) * 2;",
+            "var l = [1, 2, 3]; l[1] = l[0] + l[2]; print l, len(l) % 2;",
+            "class P { init(x) { this.x = x; } } print P(4).x * (P(2).x + 1);",
+            "var i = 0; print (i++, i++, i) + 1;",
+            "print 2 ** 3 ** 2, 7 & 3 | 8 ^ 1;",
+        ];
+
+        for program in programs {
+            assert_eq!(
+                run_with(program, |interpreter| interpreter
+                    .with_iterative_evaluation(true))
+                .unwrap(),
+                run(program).unwrap(),
+                "{program}"
+            );
+        }
+
+        let error = run_with("print 1 + -\"a\";", |interpreter| {
+            interpreter.with_iterative_evaluation(true)
+        });
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            run("print 1 + -\"a\";").unwrap_err().to_string()
+        );
+    }
+}
@@ -1,11 +1,14 @@
 use crate::token::{KEYWORDS, Literal, Token, TokenKind};
+use std::borrow::Cow;
 use thiserror::Error;
 
 #[derive(Debug)]
 pub struct Lexer<'a> {
     cursor: LexerCursor<'a>,
     tokens: Vec<Token<'a>>,
-    had_error: bool,
+    errors: Vec<LexError>,
+    negative_literals: bool,
+    normalize_numbers: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -13,16 +16,42 @@ impl<'a> Lexer<'a> {
         Self {
             cursor: LexerCursor::new(src),
             tokens: Vec::new(),
-            had_error: false,
+            errors: Vec::new(),
+            negative_literals: false,
+            normalize_numbers: false,
         }
     }
 
-    pub fn scan_tokens(mut self) -> (Vec<Token<'a>>, bool) {
+    /// Lexes a `-` directly followed by a digit as part of a negative number
+    /// literal, unless the previous token ends a value (a literal, an
+    /// identifier, `this`/`super`, a closing paren or bracket, or a postfix
+    /// `++`/`--`). In that position the `-` stays a binary minus, so `a-5`,
+    /// `xs[0]-1` and `i++-1` are still subtractions while `-5` and `a - -5`
+    /// produce the literal `-5`.
+    pub const fn with_negative_literals(mut self, enabled: bool) -> Self {
+        self.negative_literals = enabled;
+        self
+    }
+
+    /// Displays number tokens with their literal's formatting as the lexeme
+    /// (`007` becomes `7.0`, `0x10` becomes `16.0`). The raw source slice
+    /// stays available as `Token::lexeme`.
+    pub const fn with_normalized_numbers(mut self, enabled: bool) -> Self {
+        self.normalize_numbers = enabled;
+        self
+    }
+
+    pub fn scan_tokens(mut self) -> ScanResult<'a> {
         while !self.cursor.is_at_end() {
             self.scan_token();
         }
+        self.cursor.reset_slice_offset();
         self.add_token(TokenKind::EOF);
-        (self.tokens, self.had_error)
+
+        ScanResult {
+            tokens: self.tokens,
+            errors: self.errors,
+        }
     }
 
     fn scan_token(&mut self) {
@@ -34,20 +63,56 @@ impl<'a> Lexer<'a> {
                 ')' => self.add_token(TokenKind::RightParen),
                 '{' => self.add_token(TokenKind::LeftBrace),
                 '}' => self.add_token(TokenKind::RightBrace),
+                '[' => self.add_token(TokenKind::LeftBracket),
+                ']' => self.add_token(TokenKind::RightBracket),
 
                 ',' => self.add_token(TokenKind::Comma),
                 '.' => self.add_token(TokenKind::Dot),
-                '-' => self.add_token(TokenKind::Minus),
-                '+' => self.add_token(TokenKind::Plus),
+                '-' => {
+                    if self.follows_increment_target() && self.cursor.matches('-') {
+                        self.add_token(TokenKind::MinusMinus);
+                    } else if self.starts_negative_literal() {
+                        if let Err(e) = self.number() {
+                            self.errors.push(e);
+                        }
+                    } else {
+                        self.add_token(TokenKind::Minus);
+                    }
+                }
+                '+' => {
+                    let kind = if self.follows_increment_target() && self.cursor.matches('+') {
+                        TokenKind::PlusPlus
+                    } else {
+                        TokenKind::Plus
+                    };
+                    self.add_token(kind);
+                }
                 ';' => self.add_token(TokenKind::Semicolon),
                 '/' => {
                     if self.cursor.matches('/') {
                         self.comment();
+                    } else if self.cursor.matches('*') {
+                        if let Err(e) = self.block_comment() {
+                            self.errors.push(e);
+                        }
                     } else {
                         self.add_token(TokenKind::Slash);
                     }
                 }
-                '*' => self.add_token(TokenKind::Star),
+                '*' => {
+                    let kind = if self.cursor.matches('*') {
+                        TokenKind::StarStar
+                    } else {
+                        TokenKind::Star
+                    };
+                    self.add_token(kind);
+                }
+                '%' => self.add_token(TokenKind::Percent),
+                '&' => self.add_token(TokenKind::Ampersand),
+                '|' => self.add_token(TokenKind::Pipe),
+                '^' => self.add_token(TokenKind::Caret),
+                '?' => self.add_token(TokenKind::Question),
+                ':' => self.add_token(TokenKind::Colon),
 
                 '!' => {
                     let kind = if self.cursor.matches('=') {
@@ -84,14 +149,26 @@ impl<'a> Lexer<'a> {
 
                 c if c.is_ascii_digit() => {
                     if let Err(e) = self.number() {
-                        eprintln!("{e}");
+                        self.errors.push(e);
                     }
                 }
 
                 '"' => {
-                    if let Err(e) = self.string() {
-                        self.had_error = true;
-                        eprintln!("{e}");
+                    let result = if self.cursor.starts_with("\"\"") {
+                        self.block_string()
+                    } else {
+                        self.string(TokenKind::String)
+                    };
+
+                    if let Err(e) = result {
+                        self.errors.push(e);
+                    }
+                }
+
+                'b' if self.cursor.peek() == Some('"') => {
+                    self.cursor.advance();
+                    if let Err(e) = self.string(TokenKind::ByteString) {
+                        self.errors.push(e);
                     }
                 }
 
@@ -100,32 +177,74 @@ impl<'a> Lexer<'a> {
                 ' ' | '\r' | '\t' | '\n' => {}
 
                 _ => {
-                    self.had_error = true;
-                    eprintln!(
-                        "{}",
-                        LexError::UnexpectedChar {
-                            line: self.cursor.line,
-                            c,
-                        }
-                    );
+                    self.errors
+                        .push(self.error(LexErrorKind::UnexpectedChar(c)));
                 }
             }
         }
     }
 
+    /// A token starting where the current slice does.
+    fn token(&self, kind: TokenKind, lexeme: &'a str, literal: Option<Literal<'a>>) -> Token<'a> {
+        Token::new(
+            kind,
+            lexeme,
+            literal,
+            self.cursor.line,
+            self.cursor.column(),
+        )
+        .with_offset(self.cursor.offset())
+    }
+
     fn add_token(&mut self, kind: TokenKind) {
         match kind {
-            TokenKind::EOF => self
+            TokenKind::EOF => self.tokens.push(self.token(kind, "", None)),
+
+            _ => self
                 .tokens
-                .push(Token::new(kind, "", None, self.cursor.line)),
-
-            _ => self.tokens.push(Token::new(
-                kind,
-                self.cursor.slice(),
-                None,
-                self.cursor.line,
-            )),
+                .push(self.token(kind, self.cursor.slice(), None)),
+        }
+    }
+
+    /// `++` and `--` are postfix operators only straight after something
+    /// that could be incremented. Elsewhere, as in `--5` or `1--1`, they are
+    /// two separate signs.
+    fn follows_increment_target(&self) -> bool {
+        self.tokens.last().is_some_and(|token| {
+            matches!(
+                token.kind,
+                TokenKind::Identifier | TokenKind::RightParen | TokenKind::RightBracket
+            )
+        })
+    }
+
+    fn starts_negative_literal(&mut self) -> bool {
+        if !self.negative_literals || !self.cursor.peek().is_some_and(|c| c.is_ascii_digit()) {
+            return false;
         }
+
+        !self.tokens.last().is_some_and(|token| {
+            matches!(
+                token.kind,
+                TokenKind::Number
+                    | TokenKind::String
+                    | TokenKind::ByteString
+                    | TokenKind::Identifier
+                    | TokenKind::RightParen
+                    | TokenKind::RightBracket
+                    | TokenKind::PlusPlus
+                    | TokenKind::MinusMinus
+                    | TokenKind::True
+                    | TokenKind::False
+                    | TokenKind::Nil
+                    | TokenKind::This
+                    | TokenKind::Super
+            )
+        })
+    }
+
+    const fn error(&self, kind: LexErrorKind) -> LexError {
+        LexError::new(kind, self.cursor.line, self.cursor.column())
     }
 
     fn identifier(&mut self) {
@@ -140,62 +259,234 @@ impl<'a> Lexer<'a> {
         let lexeme = self.cursor.slice();
 
         if let Some(kind) = KEYWORDS.get(lexeme) {
-            self.tokens
-                .push(Token::new(*kind, lexeme, None, self.cursor.line));
+            self.tokens.push(self.token(*kind, lexeme, None));
         } else {
-            self.tokens.push(Token::new(
-                TokenKind::Identifier,
-                lexeme,
-                None,
-                self.cursor.line,
-            ));
+            self.tokens
+                .push(self.token(TokenKind::Identifier, lexeme, None));
         }
     }
 
     fn number(&mut self) -> Result<(), LexError> {
-        while self.cursor.peek().is_some_and(|c| c.is_ascii_digit()) {
-            self.cursor.advance();
+        self.consume_digits();
+
+        if matches!(self.cursor.slice(), "0" | "-0")
+            && matches!(self.cursor.peek(), Some('x' | 'X'))
+        {
+            return self.hex_number();
         }
 
+        // A `_` right after the point is taken in too, so `1._0` is reported
+        // as a misplaced separator rather than lexed as `1`, `.`, `_0`.
         if self.cursor.peek() == Some('.')
-            && self.cursor.peek_next().is_some_and(|c| c.is_ascii_digit())
+            && self
+                .cursor
+                .peek_next()
+                .is_some_and(|c| c.is_ascii_digit() || c == '_')
         {
             self.cursor.advance();
-            while self.cursor.peek().is_some_and(|c| c.is_ascii_digit()) {
-                self.cursor.advance();
+            self.consume_digits();
+        }
+
+        if matches!(self.cursor.peek(), Some('e' | 'E')) {
+            let digits_at = if matches!(self.cursor.peek_next(), Some('+' | '-')) {
+                2
+            } else {
+                1
+            };
+
+            if self
+                .cursor
+                .peek_nth(digits_at)
+                .is_some_and(|c| c.is_ascii_digit())
+            {
+                for _ in 0..digits_at {
+                    self.cursor.advance();
+                }
+                self.consume_digits();
             }
         }
 
         let lexeme = self.cursor.slice();
+        let bytes = lexeme.as_bytes();
+        let misplaced_separator = bytes.iter().enumerate().any(|(i, &b)| {
+            b == b'_'
+                && !(i > 0
+                    && bytes[i - 1].is_ascii_digit()
+                    && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+        });
 
-        self.tokens.push(Token::new(
-            TokenKind::Number,
-            lexeme,
-            Some(Literal::Number(lexeme.parse::<f64>()?)),
-            self.cursor.line,
-        ));
+        if misplaced_separator {
+            return Err(self.error(LexErrorKind::InvalidNumericSeparator));
+        }
+
+        let number = lexeme
+            .replace('_', "")
+            .parse::<f64>()
+            .map_err(|e| self.error(e.into()))?;
+
+        self.push_number(lexeme, number);
+
+        Ok(())
+    }
+
+    fn hex_number(&mut self) -> Result<(), LexError> {
+        self.cursor.advance();
+
+        while self.cursor.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            self.cursor.advance();
+        }
+
+        let lexeme = self.cursor.slice();
+        let (sign, digits) = match lexeme.strip_prefix('-') {
+            Some(rest) => (-1.0, &rest[2..]),
+            None => (1.0, &lexeme[2..]),
+        };
+
+        let value = i64::from_str_radix(digits, 16)
+            .map_err(|_| self.error(LexErrorKind::InvalidHexLiteral))?;
+
+        #[allow(clippy::cast_precision_loss)]
+        self.push_number(lexeme, sign * value as f64);
 
         Ok(())
     }
 
-    fn string(&mut self) -> Result<(), LexError> {
-        while self.cursor.peek().is_some_and(|c| c != '"') {
+    fn push_number(&mut self, lexeme: &'a str, number: f64) {
+        let token = self.token(TokenKind::Number, lexeme, Some(Literal::Number(number)));
+
+        self.tokens.push(if self.normalize_numbers {
+            token.with_normalized_lexeme(Literal::Number(number).to_string())
+        } else {
+            token
+        });
+    }
+
+    fn consume_digits(&mut self) {
+        while self
+            .cursor
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || c == '_')
+        {
+            self.cursor.advance();
+        }
+    }
+
+    /// Scans the rest of a `"..."` or `b"..."` string, whose opening quote
+    /// has been consumed. Both take the same escapes; a byte string's value
+    /// is read as UTF-8 bytes by the parser.
+    fn string(&mut self, kind: TokenKind) -> Result<(), LexError> {
+        let (line, column) = (self.cursor.line, self.cursor.column());
+
+        while let Some(c) = self.cursor.peek()
+            && c != '"'
+        {
             self.cursor.advance();
+            if c == '\\' {
+                self.cursor.advance();
+            }
         }
 
         if self.cursor.advance() != Some('"') {
-            return Err(LexError::UnterminatedString {
-                line: self.cursor.line,
-            });
+            return Err(LexError::new(
+                LexErrorKind::UnterminatedString,
+                line,
+                column,
+            ));
+        }
+
+        let lexeme = self.cursor.slice();
+        let open = lexeme.find('"').unwrap_or_default() + 1;
+        let value = self.unescape(&lexeme[open..lexeme.len() - 1])?;
+
+        self.tokens
+            .push(self.token(kind, lexeme, Some(Literal::String(value))));
+
+        Ok(())
+    }
+
+    fn unescape(&self, raw: &'a str) -> Result<Cow<'a, str>, LexError> {
+        if !raw.contains('\\') {
+            return Ok(Cow::Borrowed(raw));
+        }
+
+        let mut value = String::with_capacity(raw.len());
+        let mut chars = raw.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('\\') => value.push('\\'),
+                Some('"') => value.push('"'),
+                Some('u') => value.push(self.unicode_escape(&mut chars)?),
+                c => {
+                    return Err(self.error(LexErrorKind::InvalidEscape(c.unwrap_or_default())));
+                }
+            }
+        }
+
+        Ok(Cow::Owned(value))
+    }
+
+    fn unicode_escape(&self, chars: &mut std::str::Chars<'_>) -> Result<char, LexError> {
+        let error = self.error(LexErrorKind::InvalidUnicodeEscape);
+
+        if chars.next() != Some('{') {
+            return Err(error);
+        }
+
+        let rest = chars.as_str();
+        let Some(end) = rest.find('}') else {
+            return Err(error);
+        };
+        let digits = &rest[..end];
+
+        if digits.is_empty() || digits.len() > 6 {
+            return Err(error);
+        }
+
+        let c = u32::from_str_radix(digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(error)?;
+
+        *chars = rest[end + 1..].chars();
+        Ok(c)
+    }
+
+    fn block_string(&mut self) -> Result<(), LexError> {
+        let (line, column) = (self.cursor.line, self.cursor.column());
+        self.cursor.advance();
+        self.cursor.advance();
+
+        while !self.cursor.is_at_end() && !self.cursor.starts_with("\"\"\"") {
+            self.cursor.advance();
+        }
+
+        if self.cursor.is_at_end() {
+            return Err(LexError::new(
+                LexErrorKind::UnterminatedBlockString,
+                line,
+                column,
+            ));
+        }
+
+        for _ in 0..3 {
+            self.cursor.advance();
         }
 
         let lexeme = self.cursor.slice();
 
-        self.tokens.push(Token::new(
+        self.tokens.push(self.token(
             TokenKind::String,
             lexeme,
-            Some(Literal::String(&lexeme[1..lexeme.len() - 1])),
-            self.cursor.line,
+            Some(Literal::String(Cow::Borrowed(&lexeme[3..lexeme.len() - 3]))),
         ));
 
         Ok(())
@@ -206,6 +497,45 @@ impl<'a> Lexer<'a> {
             self.cursor.advance();
         }
     }
+
+    fn block_comment(&mut self) -> Result<(), LexError> {
+        let (line, column) = (self.cursor.line, self.cursor.column());
+        let mut depth = 1;
+
+        while !self.cursor.is_at_end() {
+            if self.cursor.starts_with("/*") {
+                depth += 1;
+                self.cursor.advance();
+            } else if self.cursor.starts_with("*/") {
+                depth -= 1;
+                self.cursor.advance();
+
+                if depth == 0 {
+                    self.cursor.advance();
+                    return Ok(());
+                }
+            }
+            self.cursor.advance();
+        }
+
+        Err(LexError::new(
+            LexErrorKind::UnterminatedBlockComment,
+            line,
+            column,
+        ))
+    }
+}
+
+#[derive(Debug)]
+pub struct ScanResult<'a> {
+    pub tokens: Vec<Token<'a>>,
+    pub errors: Vec<LexError>,
+}
+
+impl ScanResult<'_> {
+    pub const fn had_error(&self) -> bool {
+        !self.errors.is_empty()
+    }
 }
 
 #[derive(Debug)]
@@ -215,6 +545,8 @@ pub struct LexerCursor<'a> {
     position: usize,
     slice_offset: usize,
     line: usize,
+    line_start: usize,
+    slice_column: usize,
 }
 
 impl<'a> LexerCursor<'a> {
@@ -224,6 +556,8 @@ impl<'a> LexerCursor<'a> {
             position: 0,
             slice_offset: 0,
             line: 1,
+            line_start: 0,
+            slice_column: 1,
         }
     }
     pub fn matches(&mut self, expected: char) -> bool {
@@ -241,6 +575,7 @@ impl<'a> LexerCursor<'a> {
 
         if matches!(c, '\n') {
             self.line += 1;
+            self.line_start = self.position;
         }
 
         Some(c)
@@ -256,12 +591,30 @@ impl<'a> LexerCursor<'a> {
         lookahead.next().map(|(_, c)| c)
     }
 
+    pub fn starts_with(&self, pattern: &str) -> bool {
+        self.src[self.position..].starts_with(pattern)
+    }
+
+    pub fn peek_nth(&self, n: usize) -> Option<char> {
+        self.src[self.position..].chars().nth(n)
+    }
+
     pub fn peek(&mut self) -> Option<char> {
         self.src[self.position..].chars().next()
     }
 
-    pub const fn reset_slice_offset(&mut self) {
+    pub fn reset_slice_offset(&mut self) {
         self.slice_offset = self.position;
+        self.slice_column = self.src[self.line_start..self.position].chars().count() + 1;
+    }
+
+    pub const fn column(&self) -> usize {
+        self.slice_column
+    }
+
+    /// Byte offset of the current slice in the source.
+    pub const fn offset(&self) -> usize {
+        self.slice_offset
     }
 
     pub fn slice(&self) -> &'a str {
@@ -270,13 +623,418 @@ impl<'a> LexerCursor<'a> {
 }
 
 #[derive(Debug, Error)]
-pub enum LexError {
-    #[error("[line {line}] Error: Unexpected character: {c}")]
-    UnexpectedChar { line: usize, c: char },
+#[error("[line {line}:{column}] Error: {kind}")]
+pub struct LexError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: LexErrorKind,
+}
+
+impl LexError {
+    pub const fn new(kind: LexErrorKind, line: usize, column: usize) -> Self {
+        Self { line, column, kind }
+    }
+
+    pub fn line_display(&self) -> String {
+        format!("[line {}] Error: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LexErrorKind {
+    #[error("Unexpected character: {0}")]
+    UnexpectedChar(char),
+
+    #[error("Unterminated string.")]
+    UnterminatedString,
+
+    #[error("Unterminated block string.")]
+    UnterminatedBlockString,
 
-    #[error("[line {line}] Error: Unterminated string.")]
-    UnterminatedString { line: usize },
+    #[error("Invalid escape sequence: \\{0}")]
+    InvalidEscape(char),
+
+    #[error("Invalid unicode escape.")]
+    InvalidUnicodeEscape,
+
+    #[error("Invalid hex literal.")]
+    InvalidHexLiteral,
+
+    #[error("Invalid numeric separator.")]
+    InvalidNumericSeparator,
+
+    #[error("Unterminated block comment.")]
+    UnterminatedBlockComment,
 
     #[error("{0}")]
     FloatParse(#[from] std::num::ParseFloatError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lexemes(lexer: Lexer<'_>) -> Vec<String> {
+        let tokens = lexer.scan_tokens().tokens;
+        tokens[..tokens.len() - 1]
+            .iter()
+            .map(|token| token.display_lexeme().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn negative_literals_keep_subtraction_after_a_value() {
+        let negative = |source| lexemes(Lexer::new(source).with_negative_literals(true));
+
+        assert_eq!(negative("a-5"), ["a", "-", "5"]);
+        assert_eq!(negative("-5"), ["-5"]);
+        assert_eq!(negative("a - -5"), ["a", "-", "-5"]);
+    }
+
+    #[test]
+    fn minus_is_always_an_operator_by_default() {
+        assert_eq!(lexemes(Lexer::new("-5")), ["-", "5"]);
+        assert_eq!(lexemes(Lexer::new("a - -5")), ["a", "-", "-", "5"]);
+    }
+
+    #[test]
+    fn normalized_numbers_use_the_literal_formatting() {
+        let normalized = |source| lexemes(Lexer::new(source).with_normalized_numbers(true));
+
+        assert_eq!(
+            normalized("007 1000 1.50 0x10"),
+            ["7.0", "1000.0", "1.5", "16.0"]
+        );
+        assert_eq!(normalized("1e300"), ["1e300"]);
+        assert_eq!(lexemes(Lexer::new("007")), ["007"]);
+
+        let tokens = Lexer::new("007")
+            .with_normalized_numbers(true)
+            .scan_tokens()
+            .tokens;
+        assert_eq!(tokens[0].lexeme, "007");
+        assert_eq!(tokens[0].to_string(), "NUMBER 7.0 7.0");
+    }
+
+    #[test]
+    fn block_strings_keep_newlines_and_quotes() {
+        let source = "\"\"\"say \"hi\"\nand \\n bye\"\"\"\nx";
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+
+        let Some(Literal::String(text)) = &tokens[0].literal else {
+            panic!("expected a string literal, got {:?}", tokens[0]);
+        };
+        assert_eq!(text, "say \"hi\"\nand \\n bye");
+        assert_eq!(tokens[1].lexeme, "x");
+        assert_eq!(tokens[1].line, 3);
+    }
+
+    #[test]
+    fn unterminated_block_string_reports_the_opening_line() {
+        let result = Lexer::new("var a;\nvar s = \"\"\"open\nstill open").scan_tokens();
+        assert_eq!(
+            result.errors[0].line_display(),
+            "[line 2] Error: Unterminated block string."
+        );
+    }
+
+    fn scan(source: &str) -> Vec<String> {
+        Lexer::new(source)
+            .scan_tokens()
+            .tokens
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn scan_errors(source: &str) -> Vec<String> {
+        Lexer::new(source)
+            .scan_tokens()
+            .errors
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn scan_result_collects_every_error_and_keeps_scanning() {
+        let result = Lexer::new("@ 1 $\n#").scan_tokens();
+
+        assert!(result.had_error());
+        assert_eq!(result.errors.len(), 3);
+        assert_eq!(result.errors[2].line, 2);
+        assert_eq!(result.tokens.len(), 2);
+        assert!(!Lexer::new("1").scan_tokens().had_error());
+        assert_eq!(scan("1"), ["NUMBER 1 1.0", "EOF  null"]);
+        assert_eq!(
+            scan_errors("$")[0],
+            "[line 1:1] Error: Unexpected character: $"
+        );
+    }
+
+    #[test]
+    fn block_comments_are_skipped_and_count_lines() {
+        let tokens = Lexer::new("var a = 1; /* this\nspans lines */ print a;")
+            .scan_tokens()
+            .tokens;
+
+        let lexemes = tokens.iter().map(|token| token.lexeme).collect::<Vec<_>>();
+        assert_eq!(lexemes, ["var", "a", "=", "1", ";", "print", "a", ";", ""]);
+        assert_eq!(tokens[5].line, 2);
+    }
+
+    #[test]
+    fn unterminated_block_comment_reports_the_opening_line() {
+        let result = Lexer::new("1;\n/* open\nnever closed").scan_tokens();
+        assert_eq!(
+            result.errors[0].line_display(),
+            "[line 2] Error: Unterminated block comment."
+        );
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        assert_eq!(
+            scan("1 /* outer /* inner */ still commented */ 2"),
+            ["NUMBER 1 1.0", "NUMBER 2 2.0", "EOF  null"]
+        );
+
+        let result = Lexer::new("/* outer\n/* inner */ still open").scan_tokens();
+        assert_eq!(
+            result.errors[0].line_display(),
+            "[line 1] Error: Unterminated block comment."
+        );
+    }
+
+    fn string_literal(source: &str) -> String {
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+        match &tokens[0].literal {
+            Some(Literal::String(text)) => text.to_string(),
+            other => panic!("expected a string literal, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn escape_sequences_are_decoded_in_the_literal() {
+        assert_eq!(string_literal(r#""tab\there""#), "tab\there");
+        assert_eq!(string_literal(r#""a\nb\r\\\"""#), "a\nb\r\\\"");
+
+        let tokens = Lexer::new(r#""tab\there""#).scan_tokens().tokens;
+        assert_eq!(tokens[0].lexeme, r#""tab\there""#);
+    }
+
+    #[test]
+    fn unknown_escapes_are_rejected() {
+        assert_eq!(
+            scan_errors(r#""bad \q""#),
+            [r"[line 1:1] Error: Invalid escape sequence: \q"]
+        );
+    }
+
+    #[test]
+    fn unicode_escapes_decode_to_chars() {
+        assert_eq!(string_literal(r#""\u{48}\u{49}""#), "HI");
+        assert_eq!(string_literal(r#""\u{1F600}""#), "😀");
+
+        for source in [r#""\u{}""#, r#""\u{110000}""#, r#""\u{D800}""#, r#""\u48""#] {
+            assert_eq!(
+                scan_errors(source),
+                ["[line 1:1] Error: Invalid unicode escape."],
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn scientific_notation_is_one_number_token() {
+        assert_eq!(
+            scan("1e3 1.5E2 2.5e-3 6.022e23"),
+            [
+                "NUMBER 1e3 1000.0",
+                "NUMBER 1.5E2 150.0",
+                "NUMBER 2.5e-3 0.0025",
+                "NUMBER 6.022e23 6.022e23",
+                "EOF  null"
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_literals_use_the_whole_number_rule() {
+        assert_eq!(
+            scan("0xFF 0x10 0Xab"),
+            [
+                "NUMBER 0xFF 255.0",
+                "NUMBER 0x10 16.0",
+                "NUMBER 0Xab 171.0",
+                "EOF  null"
+            ]
+        );
+        assert_eq!(
+            scan_errors("0x"),
+            ["[line 1:1] Error: Invalid hex literal."]
+        );
+    }
+
+    #[test]
+    fn digit_separators_are_stripped() {
+        assert_eq!(
+            scan("1_000_000 3.141_592"),
+            [
+                "NUMBER 1_000_000 1000000.0",
+                "NUMBER 3.141_592 3.141592",
+                "EOF  null"
+            ]
+        );
+    }
+
+    #[test]
+    fn misplaced_separators_are_rejected() {
+        for source in ["1__0", "1_.0", "1._0", "1_"] {
+            assert_eq!(
+                scan_errors(source),
+                ["[line 1:1] Error: Invalid numeric separator."],
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn tokens_record_their_starting_column() {
+        let tokens = Lexer::new("var ab\n  = 12;").scan_tokens().tokens;
+        let positions = tokens
+            .iter()
+            .map(|token| (token.lexeme, token.line, token.column))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            positions,
+            [
+                ("var", 1, 1),
+                ("ab", 1, 5),
+                ("=", 2, 3),
+                ("12", 2, 5),
+                (";", 2, 7),
+                ("", 2, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_show_the_column_with_a_line_only_form_available() {
+        let result = Lexer::new("1;\n  @").scan_tokens();
+        assert_eq!(
+            result.errors[0].to_string(),
+            "[line 2:3] Error: Unexpected character: @"
+        );
+        assert_eq!(
+            result.errors[0].line_display(),
+            "[line 2] Error: Unexpected character: @"
+        );
+    }
+
+    #[test]
+    fn minus_after_a_closing_bracket_is_subtraction() {
+        let negative = |source| lexemes(Lexer::new(source).with_negative_literals(true));
+
+        assert_eq!(negative("xs[0]-1"), ["xs", "[", "0", "]", "-", "1"]);
+        assert_eq!(negative("[-1]"), ["[", "-1", "]"]);
+    }
+
+    #[test]
+    fn number_literals_print_without_exponents() {
+        assert_eq!(
+            scan("123456789.0 0.0001 1.5"),
+            [
+                "NUMBER 123456789.0 123456789.0",
+                "NUMBER 0.0001 0.0001",
+                "NUMBER 1.5 1.5",
+                "EOF  null",
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_string_reports_the_opening_line() {
+        assert_eq!(
+            scan_errors("1\n2\nprint \"abc\nmore\nlines"),
+            ["[line 3:7] Error: Unterminated string."]
+        );
+        assert_eq!(
+            scan_errors("\""),
+            ["[line 1:1] Error: Unterminated string."]
+        );
+    }
+
+    #[test]
+    fn star_star_is_one_token() {
+        assert_eq!(
+            scan("2**3*4"),
+            [
+                "NUMBER 2 2.0",
+                "STAR_STAR ** null",
+                "NUMBER 3 3.0",
+                "STAR * null",
+                "NUMBER 4 4.0",
+                "EOF  null",
+            ]
+        );
+    }
+
+    #[test]
+    fn minus_after_a_postfix_operator_is_subtraction() {
+        let negative = |source| lexemes(Lexer::new(source).with_negative_literals(true));
+
+        assert_eq!(negative("i++-1"), ["i", "++", "-", "1"]);
+        assert_eq!(negative("i---1"), ["i", "--", "-", "1"]);
+    }
+
+    #[test]
+    fn byte_strings_take_string_escapes() {
+        assert_eq!(
+            scan(r#"b"AB" b"\u{e9}\"" b"#),
+            [
+                r#"BYTE_STRING b"AB" AB"#,
+                r#"BYTE_STRING b"\u{e9}\"" é""#,
+                "IDENTIFIER b null",
+                "EOF  null",
+            ]
+        );
+        assert_eq!(
+            scan_errors("b\"abc"),
+            ["[line 1:1] Error: Unterminated string."]
+        );
+    }
+
+    #[test]
+    fn tokens_record_their_byte_offset() {
+        let offsets: Vec<_> = Lexer::new("\"é\" + x;\n  nil")
+            .scan_tokens()
+            .tokens
+            .iter()
+            .map(|token| token.offset)
+            .collect();
+
+        assert_eq!(offsets, [0, 5, 7, 8, 12, 15]);
+    }
+
+    #[test]
+    fn increments_lex_only_after_an_increment_target() {
+        assert_eq!(
+            scan("a-- 1--1 --x ++"),
+            [
+                "IDENTIFIER a null",
+                "MINUS_MINUS -- null",
+                "NUMBER 1 1.0",
+                "MINUS - null",
+                "MINUS - null",
+                "NUMBER 1 1.0",
+                "MINUS - null",
+                "MINUS - null",
+                "IDENTIFIER x null",
+                "PLUS_PLUS ++ null",
+                "EOF  null",
+            ]
+        );
+    }
+}
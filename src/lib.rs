@@ -1,5 +1,61 @@
+pub mod cli;
+pub mod environment;
 pub mod errors;
 pub mod grammar;
+pub mod interpreter;
 pub mod lexer;
+pub mod natives;
 pub mod parser;
+pub mod repl;
+pub mod resolver;
 pub mod token;
+pub mod value;
+
+use errors::InterpreterError;
+use interpreter::Interpreter;
+use lexer::Lexer;
+use parser::{ParseError, Parser};
+use resolver::Resolver;
+use value::LiteralValue;
+
+/// Evaluates a single expression, as the `evaluate` command does, and
+/// returns its value. Only the first lex error is reported.
+pub fn run_source(source: &str) -> Result<LiteralValue<'_>, InterpreterError> {
+    let result = Lexer::new(source).scan_tokens();
+    if let Some(error) = result.errors.into_iter().next() {
+        return Err(error.into());
+    }
+
+    let expr = Parser::new(&result.tokens).parse()?;
+
+    Ok(Interpreter::new().evaluate(&expr)?)
+}
+
+/// Runs a whole program, as the `run` command does, and returns everything
+/// it printed. Only the first lex, parse or resolve error is reported.
+pub fn exec_program(source: &str) -> Result<String, InterpreterError> {
+    let result = Lexer::new(source).scan_tokens();
+    if let Some(error) = result.errors.into_iter().next() {
+        return Err(error.into());
+    }
+
+    let statements = Parser::new(&result.tokens)
+        .parse_statements()
+        .map_err(first_error)?;
+
+    let mut output = Vec::new();
+    let mut interpreter = Interpreter::with_writer(&mut output);
+
+    Resolver::new(&mut interpreter)
+        .resolve(&statements)
+        .map_err(first_error)?;
+    interpreter.run(&statements)?;
+    drop(interpreter);
+
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+// Parsing and resolving only fail with at least one error.
+pub(crate) fn first_error(mut errors: Vec<ParseError>) -> InterpreterError {
+    errors.swap_remove(0).into()
+}
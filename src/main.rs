@@ -1,88 +1,261 @@
-use codecrafters_interpreter::{errors::InterpreterError, lexer::Lexer, parser::Parser};
+use codecrafters_interpreter::{
+    cli::{self, Args},
+    errors::InterpreterError,
+    grammar::Statement,
+    interpreter::Interpreter,
+    lexer::{LexError, Lexer},
+    parser::{ParseError, Parser},
+    repl::{Repl, StdinLineReader},
+    resolver::Resolver,
+    token::Token,
+};
 use std::env;
 use std::fs;
-use std::io::{self, Write};
+use std::io;
+use std::path::Path;
 
 fn main() -> Result<(), InterpreterError> {
-    let mut args = env::args();
-    let program = args.next();
-
-    match (args.next(), args.next()) {
-        (Some(command), None) => run_prompt(&command),
-        (Some(command), Some(filename)) => run_file(&command, &filename),
-        _ => {
-            eprintln!(
-                "Usage: {} [tokenize <filename>]",
-                program.unwrap_or_default()
-            );
-            std::process::exit(1);
-        }
+    let args = cli::parse(env::args()).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(e.exit_code())
+    });
+
+    match (&args.source, &args.filename) {
+        (Some(source), _) => run(&args, source, None),
+        (None, Some(filename)) => run_file(&args, filename),
+        (None, None) if args.command == "repl" => run_repl(),
+        (None, None) => run_prompt(&args),
     }
 }
 
+/// `script` is the file `src` was read from, if any; `import` paths are
+/// resolved against its directory.
 #[allow(clippy::single_match_else)]
-fn run(command: &str, src: &str) -> Result<(), InterpreterError> {
-    match command {
+fn run(args: &Args, src: &str, script: Option<&Path>) -> Result<(), InterpreterError> {
+    match args.command.as_str() {
         "tokenize" => {
-            let (tokens, had_error) = Lexer::new(src).scan_tokens();
+            let result = Lexer::new(src)
+                .with_normalized_numbers(args.normalize_numbers)
+                .scan_tokens();
+
+            for error in &result.errors {
+                eprintln!("{error}");
+            }
 
-            for token in tokens {
+            for token in &result.tokens {
                 println!("{token}");
             }
 
-            if had_error {
+            if result.had_error() {
                 std::process::exit(65)
             }
 
             Ok(())
         }
         "parse" => {
-            let (tokens, _) = Lexer::new(src).scan_tokens();
-            match Parser::new(&tokens).expression() {
+            let tokens = scan(src);
+
+            match Parser::new(&tokens).parse() {
+                Ok(expr) if args.json => {
+                    println!("{}", expr.to_json());
+                }
+                // With --json a whole program is accepted too, as an array of
+                // statements; a lone expression still prints as one object.
+                // When neither parses, the program's errors are reported.
+                Err(_) if args.json => match Parser::new(&tokens).parse_statements() {
+                    Ok(statements) => {
+                        let statements = statements
+                            .iter()
+                            .map(Statement::to_json)
+                            .collect::<Vec<_>>();
+                        println!("[{}]", statements.join(","));
+                    }
+                    Err(errors) => {
+                        for error in &errors {
+                            eprintln!("{error}");
+                        }
+                        std::process::exit(65)
+                    }
+                },
                 Ok(expr) => {
                     println!("{expr}");
                 }
                 Err(e) => {
                     eprintln!("{e}");
+                    std::process::exit(65)
                 }
             }
 
             Ok(())
         }
+        "evaluate" => {
+            let tokens = scan(src);
+
+            let expr = Parser::new(&tokens).parse().unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(65)
+            });
+
+            match Interpreter::new().evaluate(&expr) {
+                Ok(value) => {
+                    println!("{value}");
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(70)
+                }
+            }
+
+            Ok(())
+        }
+        "run" => {
+            let result = Lexer::new(src).scan_tokens();
+            let program = Parser::new(&result.tokens).parse_program();
+
+            let mut errors = result
+                .errors
+                .iter()
+                .map(lex_diagnostic)
+                .chain(program.errors.iter().map(parse_diagnostic))
+                .collect::<Vec<_>>();
+
+            let mut interpreter = match script {
+                Some(path) => Interpreter::new().with_script_path(path),
+                None => Interpreter::new(),
+            };
+
+            // Resolving a program with holes in it would only add noise.
+            if errors.is_empty()
+                && let Err(resolve_errors) =
+                    Resolver::new(&mut interpreter).resolve(&program.statements)
+            {
+                errors.extend(resolve_errors.iter().map(parse_diagnostic));
+            }
+
+            if !errors.is_empty() {
+                report_errors(errors);
+            }
+
+            if let Err(e) = interpreter.run(&program.statements) {
+                eprintln!("{e}");
+                std::process::exit(70)
+            }
+
+            Ok(())
+        }
+
+        _ => Err(InterpreterError::UnknownCommand(args.command.clone())),
+    }
+}
 
-        _ => Err(InterpreterError::UnknownCommand(command.into())),
+fn scan(src: &str) -> Vec<Token<'_>> {
+    let result = Lexer::new(src).scan_tokens();
+
+    if result.had_error() {
+        report_errors(result.errors.iter().map(lex_diagnostic).collect());
     }
+
+    result.tokens
 }
 
-fn run_prompt(command: &str) -> Result<(), InterpreterError> {
-    let stdin = io::stdin();
-    let mut input = String::new();
+/// A compile error's message keyed by its `(line, column)`.
+type Diagnostic = ((usize, usize), String);
+
+fn lex_diagnostic(error: &LexError) -> Diagnostic {
+    ((error.line, error.column), error.to_string())
+}
+
+fn parse_diagnostic(error: &ParseError) -> Diagnostic {
+    ((error.token.line, error.token.column), error.to_string())
+}
 
-    loop {
-        print!("> ");
-        io::stdout().flush()?;
+/// Prints `errors` in source order, followed by a count when there is more
+/// than one, and exits with the compile-error status.
+fn report_errors(mut errors: Vec<Diagnostic>) -> ! {
+    errors.sort_by_key(|(position, _)| *position);
 
-        input.clear();
-        let bytes_read = stdin.read_line(&mut input)?;
+    for (_, message) in &errors {
+        eprintln!("{message}");
+    }
+
+    if errors.len() > 1 {
+        eprintln!("{} errors found.", errors.len());
+    }
+
+    std::process::exit(65)
+}
+
+fn run_prompt(args: &Args) -> Result<(), InterpreterError> {
+    Repl::new(StdinLineReader).run(|line| run(args, line, None))
+}
 
-        if bytes_read == 0 {
-            break;
+fn run_repl() -> Result<(), InterpreterError> {
+    let mut interpreter = Interpreter::new();
+
+    Repl::new(StdinLineReader).run(|line| {
+        // Functions, classes and lambdas keep borrowing the line they were
+        // declared on for as long as the session can call them, so every
+        // line is leaked. A session grows by the text typed into it.
+        eval_line(&mut interpreter, Box::leak(line.into()));
+
+        Ok(())
+    })
+}
+
+fn eval_line(interpreter: &mut Interpreter<'static>, src: &'static str) {
+    let result = Lexer::new(src).scan_tokens();
+
+    if result.had_error() {
+        for error in &result.errors {
+            eprintln!("{error}");
+        }
+        return;
+    }
+
+    let tokens = result.tokens;
+
+    if let Ok(expr) = Parser::new(&tokens).parse() {
+        match interpreter.evaluate(&expr) {
+            Ok(value) => println!("{value}"),
+            Err(e) => eprintln!("{e}"),
         }
+        return;
+    }
 
-        let line = input.trim_end();
-        if line.is_empty() {
-            continue;
+    let statements = match Parser::new(&tokens).parse_statements() {
+        Ok(statements) => statements,
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{error}");
+            }
+            return;
         }
+    };
 
-        run(command, line)?;
+    if let Err(errors) = Resolver::new(interpreter).resolve(&statements) {
+        for error in &errors {
+            eprintln!("{error}");
+        }
+        return;
     }
 
-    Ok(())
+    if let Err(e) = interpreter.run(&statements) {
+        eprintln!("{e}");
+    }
 }
 
-fn run_file(command: &str, filename: &str) -> Result<(), InterpreterError> {
-    let src =
-        fs::read_to_string(filename).map_err(|e| InterpreterError::FileRead(filename.into(), e))?;
+fn run_file(args: &Args, filename: &str) -> Result<(), InterpreterError> {
+    let src = if filename == "-" {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(filename)
+    };
+
+    let src = src.unwrap_or_else(|e| {
+        eprintln!("{}", InterpreterError::FileRead(filename.into(), e));
+        std::process::exit(66)
+    });
 
-    run(command, &src)
+    let script = (filename != "-").then(|| Path::new(filename));
+    run(args, &src, script)
 }
@@ -0,0 +1,458 @@
+use crate::{
+    environment::Environment,
+    interpreter::{Interpreter, RuntimeErrorKind},
+    value::{Arity, LiteralValue, NativeFn, NativeFunction, format_number},
+};
+use std::{
+    collections::HashSet,
+    io, mem,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub fn install(environment: &mut Environment<'_>) {
+    define(environment, "clock", Arity::Exact(0), clock);
+    define(environment, "len", Arity::Exact(1), len);
+    define(environment, "printf", Arity::AtLeast(1), printf);
+    define(environment, "charAt", Arity::Exact(2), char_at);
+    define(environment, "substr", Arity::Exact(3), substr);
+    define(environment, "toNumber", Arity::Exact(1), to_number);
+    define(environment, "toString", Arity::Exact(1), to_string);
+    define(environment, "readLine", Arity::Exact(0), read_line);
+    define(environment, "assert", Arity::Between(1, 2), assert);
+    define(environment, "type", Arity::Exact(1), type_of);
+    define(environment, "compare", Arity::Exact(2), compare);
+    define(environment, "toFixed", Arity::Exact(2), to_fixed);
+    define(
+        environment,
+        "toExponential",
+        Arity::Exact(2),
+        to_exponential,
+    );
+    define(environment, "toPrecision", Arity::Exact(2), to_precision);
+    define(environment, "breakpoint", Arity::Exact(1), breakpoint);
+    define(environment, "sizeOf", Arity::Exact(1), size_of);
+    define(environment, "freeze", Arity::Exact(1), freeze);
+}
+
+const MAX_FORMAT_DIGITS: usize = 100;
+
+/// How many levels of nested lists `sizeOf` looks into.
+const MAX_SIZE_DEPTH: usize = 64;
+
+fn define<'a>(
+    environment: &mut Environment<'a>,
+    name: &'static str,
+    arity: Arity,
+    function: NativeFn<'a>,
+) {
+    environment.define(
+        name,
+        LiteralValue::NativeFunction(Rc::new(NativeFunction {
+            name,
+            arity,
+            function,
+        })),
+    );
+}
+
+fn clock<'a>(
+    _: &mut Interpreter<'a>,
+    _: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let elapsed = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    Ok(LiteralValue::Number(elapsed.as_secs_f64()))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn len<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let len = match &arguments[0] {
+        LiteralValue::String(string) => string.chars().count(),
+        LiteralValue::List(list) => list.elements.borrow().len(),
+        _ => return Err(RuntimeErrorKind::InvalidArgument("len", "a string or list")),
+    };
+
+    Ok(LiteralValue::Number(len as f64))
+}
+
+fn printf<'a>(
+    interpreter: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let LiteralValue::String(format) = &arguments[0] else {
+        return Err(RuntimeErrorKind::Native {
+            name: "printf",
+            message: "first argument must be a string",
+        });
+    };
+
+    let mut pieces = format.split("{}");
+    let mut values = arguments[1..].iter();
+    let mut output = pieces.next().unwrap_or_default().to_string();
+
+    for piece in pieces {
+        let Some(value) = values.next() else {
+            return Err(RuntimeErrorKind::Native {
+                name: "printf",
+                message: "not enough arguments",
+            });
+        };
+
+        output.push_str(&value.as_string());
+        output.push_str(piece);
+    }
+
+    interpreter.write_output(&output)?;
+    interpreter.writer().flush()?;
+
+    Ok(LiteralValue::Nil)
+}
+
+fn char_at<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let (LiteralValue::String(string), LiteralValue::Number(index)) =
+        (&arguments[0], &arguments[1])
+    else {
+        return Err(RuntimeErrorKind::InvalidArgument(
+            "charAt",
+            "a string and a number",
+        ));
+    };
+
+    string
+        .chars()
+        .nth(string_index(*index)?)
+        .map(|c| LiteralValue::String(c.to_string()))
+        .ok_or(RuntimeErrorKind::StringIndexOutOfBounds)
+}
+
+fn substr<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let (LiteralValue::String(string), LiteralValue::Number(start), LiteralValue::Number(len)) =
+        (&arguments[0], &arguments[1], &arguments[2])
+    else {
+        return Err(RuntimeErrorKind::InvalidArgument(
+            "substr",
+            "a string and two numbers",
+        ));
+    };
+
+    let (start, len) = (string_index(*start)?, string_index(*len)?);
+    if start
+        .checked_add(len)
+        .is_none_or(|end| end > string.chars().count())
+    {
+        return Err(RuntimeErrorKind::StringIndexOutOfBounds);
+    }
+
+    Ok(LiteralValue::String(
+        string.chars().skip(start).take(len).collect(),
+    ))
+}
+
+fn to_number<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    match &arguments[0] {
+        LiteralValue::Number(n) => Ok(LiteralValue::Number(*n)),
+        // Rust also parses "inf" and "NaN", which aren't Lox number literals.
+        LiteralValue::String(string) => string
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(LiteralValue::Number)
+            .ok_or(RuntimeErrorKind::NotConvertibleToNumber),
+        _ => Err(RuntimeErrorKind::NotConvertibleToNumber),
+    }
+}
+
+fn to_string<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    Ok(LiteralValue::String(arguments[0].as_string()))
+}
+
+fn read_line<'a>(
+    interpreter: &mut Interpreter<'a>,
+    _: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    interpreter.writer().flush()?;
+
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Ok(LiteralValue::Nil);
+    }
+
+    let len = line.trim_end_matches(['\n', '\r']).len();
+    line.truncate(len);
+
+    Ok(LiteralValue::String(line))
+}
+
+/// Logs the variables in scope to stderr when the condition is truthy and,
+/// with interactive breakpoints on, waits for a line on stdin.
+fn breakpoint<'a>(
+    interpreter: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    if !arguments[0].is_truthy() {
+        return Ok(LiteralValue::Nil);
+    }
+
+    interpreter.writer().flush()?;
+    eprint!(
+        "breakpoint:\n{}",
+        Environment::dump(interpreter.environment())
+    );
+
+    if interpreter.interactive_breakpoints() {
+        io::stdin().read_line(&mut String::new())?;
+    }
+
+    Ok(LiteralValue::Nil)
+}
+
+fn assert<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    if arguments[0].is_truthy() {
+        return Ok(LiteralValue::Nil);
+    }
+
+    let message = arguments
+        .get(1)
+        .map_or_else(|| "Assertion failed.".to_string(), LiteralValue::as_string);
+
+    Err(RuntimeErrorKind::AssertionFailed(message))
+}
+
+fn type_of<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    Ok(LiteralValue::String(arguments[0].type_name().to_string()))
+}
+
+/// Freezes a list or instance in place and returns it. Other values are
+/// already immutable and come back unchanged.
+fn freeze<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    match &arguments[0] {
+        LiteralValue::List(list) => list.freeze(),
+        LiteralValue::Instance(instance) => instance.freeze(),
+        _ => {}
+    }
+
+    Ok(arguments[0].clone())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn size_of<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let size = approximate_size(&arguments[0], 0, &mut HashSet::new());
+    Ok(LiteralValue::Number(size as f64))
+}
+
+/// Approximate bytes held by `value`. Each list is counted once, however
+/// often it is reachable, so a list containing itself terminates; lists
+/// nested deeper than `MAX_SIZE_DEPTH` count only their own header.
+/// Functions, classes, instances and modules count as the pointer to them.
+fn approximate_size(value: &LiteralValue<'_>, depth: usize, seen: &mut HashSet<usize>) -> usize {
+    match value {
+        LiteralValue::Nil => 0,
+        LiteralValue::Number(_) | LiteralValue::Boolean(_) => 8,
+        LiteralValue::String(string) => mem::size_of::<String>() + string.len(),
+        LiteralValue::List(list) => {
+            if !seen.insert(Rc::as_ptr(list) as usize) {
+                return mem::size_of::<Rc<()>>();
+            }
+
+            let elements = if depth < MAX_SIZE_DEPTH {
+                list.elements
+                    .borrow()
+                    .iter()
+                    .map(|element| approximate_size(element, depth + 1, seen))
+                    .sum()
+            } else {
+                0
+            };
+
+            mem::size_of::<Vec<()>>() + elements
+        }
+        LiteralValue::Function(_)
+        | LiteralValue::NativeFunction(_)
+        | LiteralValue::Class(_)
+        | LiteralValue::Instance(_)
+        | LiteralValue::Module(_) => mem::size_of::<Rc<()>>(),
+    }
+}
+
+fn compare<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let ordering = arguments[0]
+        .total_cmp(&arguments[1])
+        .ok_or(RuntimeErrorKind::OperandsMustBeComparable)?;
+
+    Ok(LiteralValue::Number(f64::from(ordering as i8)))
+}
+
+fn to_fixed<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let (x, digits) = format_arguments(arguments, 0).ok_or(RuntimeErrorKind::InvalidArgument(
+        "toFixed",
+        "a number and an integer from 0 to 100",
+    ))?;
+
+    Ok(LiteralValue::String(if x.is_finite() {
+        let x = round_ties_away(x, Rounding::Decimals(digits));
+        format!("{x:.digits$}")
+    } else {
+        format_number(x)
+    }))
+}
+
+fn to_exponential<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let (x, digits) = format_arguments(arguments, 0).ok_or(RuntimeErrorKind::InvalidArgument(
+        "toExponential",
+        "a number and an integer from 0 to 100",
+    ))?;
+
+    Ok(LiteralValue::String(exponential(x, digits)))
+}
+
+/// Like JavaScript, switches to exponential notation when the exponent is
+/// below -6 or doesn't fit in `precision` digits.
+fn to_precision<'a>(
+    _: &mut Interpreter<'a>,
+    arguments: &[LiteralValue<'a>],
+) -> Result<LiteralValue<'a>, RuntimeErrorKind> {
+    let (x, precision) = format_arguments(arguments, 1).ok_or(
+        RuntimeErrorKind::InvalidArgument("toPrecision", "a number and an integer from 1 to 100"),
+    )?;
+
+    if !x.is_finite() {
+        return Ok(LiteralValue::String(format_number(x)));
+    }
+
+    let x = round_ties_away(x, Rounding::Significant(precision));
+    let digits = precision - 1;
+    let exponent = format!("{x:.digits$e}")
+        .split_once('e')
+        .and_then(|(_, exponent)| exponent.parse::<isize>().ok())
+        .unwrap_or_default();
+
+    Ok(LiteralValue::String(match usize::try_from(exponent) {
+        Ok(exponent) if exponent < precision => {
+            let digits = digits - exponent;
+            format!("{x:.digits$}")
+        }
+        Err(_) if exponent >= -6 => {
+            let digits = digits + exponent.unsigned_abs();
+            format!("{x:.digits$}")
+        }
+        _ => exponential(x, digits),
+    }))
+}
+
+/// Formats with JavaScript's explicit exponent sign, e.g. `1.5e+3`.
+fn exponential(x: f64, digits: usize) -> String {
+    if !x.is_finite() {
+        return format_number(x);
+    }
+
+    let x = round_ties_away(x, Rounding::Significant(digits + 1));
+    let formatted = format!("{x:.digits$e}");
+    match formatted.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            format!("{mantissa}e+{exponent}")
+        }
+        _ => formatted,
+    }
+}
+
+enum Rounding {
+    Decimals(usize),
+    Significant(usize),
+}
+
+/// Rust's `{:.N}` and `{:.Ne}` round a value exactly halfway between two
+/// results to even, where JavaScript picks the one further from zero, so
+/// `toFixed(2.5, 0)` is "3". Moving an exact tie one ulp outward makes Rust
+/// round it the same way; values that only look like ties, such as 1.005
+/// (really 1.00499…), are left alone.
+fn round_ties_away(x: f64, rounding: Rounding) -> f64 {
+    if !x.is_finite() || x == 0.0 {
+        return x;
+    }
+
+    // Every finite f64 has an exact decimal expansion within 1074 places.
+    let exact = format!("{:.1074}", x.abs());
+    let point = exact.find('.').unwrap_or(exact.len());
+    let digits = exact.replace('.', "");
+
+    let first_dropped = match rounding {
+        Rounding::Decimals(decimals) => point + decimals,
+        Rounding::Significant(significant) => {
+            digits.find(|c| c != '0').unwrap_or_default() + significant
+        }
+    };
+
+    let is_tie = digits
+        .get(first_dropped..)
+        .and_then(|dropped| dropped.strip_prefix('5'))
+        .is_some_and(|rest| rest.bytes().all(|b| b == b'0'));
+
+    match (is_tie, x > 0.0) {
+        (false, _) => x,
+        (true, true) => x.next_up(),
+        (true, false) => x.next_down(),
+    }
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn format_arguments(arguments: &[LiteralValue<'_>], min_digits: usize) -> Option<(f64, usize)> {
+    let (LiteralValue::Number(x), LiteralValue::Number(digits)) = (&arguments[0], &arguments[1])
+    else {
+        return None;
+    };
+
+    let in_range =
+        digits.fract() == 0.0 && (min_digits as f64..=MAX_FORMAT_DIGITS as f64).contains(digits);
+
+    in_range.then_some((*x, *digits as usize))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn string_index(index: f64) -> Result<usize, RuntimeErrorKind> {
+    if index.fract() != 0.0 || index < 0.0 {
+        return Err(RuntimeErrorKind::StringIndexOutOfBounds);
+    }
+
+    Ok(index as usize)
+}
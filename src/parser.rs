@@ -1,28 +1,992 @@
 use crate::{
-    grammar::{Expr, Literal},
-    token::{Token, TokenKind},
+    grammar::{Expr, FunctionDeclaration, Literal, Operator, Statement, next_expr_id},
+    token::{KEYWORDS, Token, TokenKind},
 };
+use std::rc::Rc;
 use thiserror::Error;
 
-pub struct Parser<'a> {
-    cursor: ParserCursor<'a>,
+const MAX_ARGUMENTS: usize = 255;
+
+pub struct Parser<'t, 'a> {
+    cursor: ParserCursor<'t, 'a>,
+    errors: Vec<ParseError>,
+    function_depth: usize,
+    loop_depth: usize,
+    condition_warnings: bool,
+    warnings: Vec<String>,
 }
 
-impl<'a> Parser<'a> {
-    pub const fn new(tokens: &'a [Token<'a>]) -> Self {
+impl<'t, 'a> Parser<'t, 'a> {
+    pub const fn new(tokens: &'t [Token<'a>]) -> Self {
         Self {
             cursor: ParserCursor::new(tokens),
+            errors: Vec::new(),
+            function_depth: 0,
+            loop_depth: 0,
+            condition_warnings: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Warns when an `if` or `while` condition is a bare assignment, the
+    /// usual sign of `=` written for `==`. Warnings never stop parsing; read
+    /// them from `warnings`. Wrap the assignment in parentheses to keep it.
+    pub const fn with_condition_warnings(mut self, enabled: bool) -> Self {
+        self.condition_warnings = enabled;
+        self
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Like `new`, for token streams that didn't come from the lexer: the
+    /// stream must end with an EOF token, which the parser relies on to stop.
+    pub fn from_tokens(tokens: &'t [Token<'a>]) -> Result<Self, ParseError> {
+        match tokens.last() {
+            Some(token) if token.kind == TokenKind::EOF => Ok(Self::new(tokens)),
+            last => Err(ParseError::new(
+                ParseErrorKind::MissingEof,
+                last.map(ErrorToken::from).unwrap_or_default(),
+            )),
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Expr<'a>, ParseError> {
+        let expr = self.expression()?;
+
+        if !self.is_complete() {
+            return Err(self.cursor.error(ParseErrorKind::TrailingTokens));
+        }
+
+        if !self.errors.is_empty() {
+            return Err(self.errors.remove(0));
+        }
+
+        Ok(expr)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cursor.is_at_end()
+    }
+
+    pub fn parse_statements(&mut self) -> Result<Vec<Statement<'a>>, Vec<ParseError>> {
+        let program = self.parse_program();
+
+        if program.errors.is_empty() {
+            Ok(program.statements)
+        } else {
+            Err(program.errors)
+        }
+    }
+
+    /// Parses every declaration it can, synchronizing past each error, and
+    /// returns the statements that parsed alongside the errors.
+    pub fn parse_program(&mut self) -> Program<'a> {
+        let mut statements = Vec::new();
+
+        while !self.is_complete() {
+            if let Some(statement) = self.recovering_declaration() {
+                statements.push(statement);
+            }
+        }
+
+        let tokens = self.cursor.tokens();
+        Program {
+            statements,
+            errors: std::mem::take(&mut self.errors),
+            token_count: tokens.len(),
+            line_count: tokens.last().map_or(0, |eof| eof.line),
+            source_len: tokens.last().map_or(0, |eof| eof.offset),
+        }
+    }
+
+    fn recovering_declaration(&mut self) -> Option<Statement<'a>> {
+        match self.declaration() {
+            Ok(statement) => Some(statement),
+            Err(e) => {
+                self.errors.push(e);
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn synchronize(&mut self) {
+        if !self.is_complete() {
+            self.cursor.advance();
+        }
+
+        while !self.is_complete() {
+            if self.cursor.previous().map(|token| token.kind) == Some(TokenKind::Semicolon) {
+                return;
+            }
+
+            if self.cursor.peek().is_some_and(|token| {
+                matches!(
+                    token.kind,
+                    TokenKind::Class
+                        | TokenKind::Fun
+                        | TokenKind::Var
+                        | TokenKind::Const
+                        | TokenKind::Import
+                        | TokenKind::StaticAssert
+                        | TokenKind::For
+                        | TokenKind::If
+                        | TokenKind::While
+                        | TokenKind::Print
+                        | TokenKind::Return
+                )
+            }) {
+                return;
+            }
+
+            self.cursor.advance();
+        }
+    }
+
+    fn declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        if self.cursor.match_token(TokenKind::Class) {
+            return self.class_declaration();
+        }
+
+        if self.cursor.match_token(TokenKind::Fun) {
+            return Ok(Statement::Function(self.function("function name")?));
+        }
+
+        if self.cursor.match_token(TokenKind::Var) {
+            return self.var_declaration();
+        }
+
+        if self.cursor.match_token(TokenKind::Const) {
+            return self.const_declaration();
+        }
+
+        if self.cursor.match_token(TokenKind::Import) {
+            return self.import_declaration();
+        }
+
+        if self.cursor.match_token(TokenKind::StaticAssert) {
+            return self.static_assert_declaration();
+        }
+
+        self.statement()
+    }
+
+    fn class_declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        let name = self
+            .cursor
+            .consume(
+                TokenKind::Identifier,
+                ParseErrorKind::Expected("class name"),
+            )?
+            .clone();
+
+        let superclass = if self.cursor.match_token(TokenKind::Less) {
+            let name = self
+                .cursor
+                .consume(
+                    TokenKind::Identifier,
+                    ParseErrorKind::Expected("superclass name"),
+                )?
+                .clone();
+
+            Some(Expr::Variable {
+                id: next_expr_id(),
+                name,
+            })
+        } else {
+            None
+        };
+
+        self.cursor.consume(
+            TokenKind::LeftBrace,
+            ParseErrorKind::Expected("'{' before class body"),
+        )?;
+
+        let mut methods = Vec::new();
+        while !self.cursor.check_token(&TokenKind::RightBrace) && !self.is_complete() {
+            methods.push(self.function("method name")?);
+        }
+
+        self.cursor.consume(
+            TokenKind::RightBrace,
+            ParseErrorKind::Expected("'}' after class body"),
+        )?;
+
+        Ok(Statement::Class {
+            name,
+            superclass,
+            methods,
+        })
+    }
+
+    fn function(&mut self, kind: &'static str) -> Result<Rc<FunctionDeclaration<'a>>, ParseError> {
+        let name = self
+            .cursor
+            .consume(TokenKind::Identifier, ParseErrorKind::Expected(kind))?
+            .clone();
+
+        self.function_body(name)
+    }
+
+    /// Parses the parameter list and body shared by named functions,
+    /// methods and lambdas.
+    fn function_body(
+        &mut self,
+        name: Token<'a>,
+    ) -> Result<Rc<FunctionDeclaration<'a>>, ParseError> {
+        self.cursor.consume(
+            TokenKind::LeftParen,
+            ParseErrorKind::Expected("'(' after function name"),
+        )?;
+
+        let mut params = Vec::new();
+        if !self.cursor.check_token(&TokenKind::RightParen) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    self.errors
+                        .push(self.cursor.error(ParseErrorKind::TooManyParameters));
+                }
+                params.push(
+                    self.cursor
+                        .consume(
+                            TokenKind::Identifier,
+                            ParseErrorKind::Expected("parameter name"),
+                        )?
+                        .clone(),
+                );
+
+                if !self.cursor.match_token(TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+
+        self.cursor.consume(
+            TokenKind::RightParen,
+            ParseErrorKind::Expected("')' after parameters"),
+        )?;
+        self.cursor.consume(
+            TokenKind::LeftBrace,
+            ParseErrorKind::Expected("'{' before function body"),
+        )?;
+
+        let enclosing_loops = std::mem::take(&mut self.loop_depth);
+        self.function_depth += 1;
+        let body = self.block();
+        self.function_depth -= 1;
+        self.loop_depth = enclosing_loops;
+        let body = body?;
+
+        Ok(Rc::new(FunctionDeclaration { name, params, body }))
+    }
+
+    fn var_declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        let name = self.variable_name("variable name")?;
+
+        let initializer = if self.cursor.match_token(TokenKind::Equal) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after variable declaration"),
+        )?;
+
+        Ok(Statement::Var { name, initializer })
+    }
+
+    fn const_declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        let name = self.variable_name("constant name")?;
+
+        if !self.cursor.match_token(TokenKind::Equal) {
+            return Err(self.cursor.error(ParseErrorKind::UninitializedConst));
+        }
+        let initializer = self.expression()?;
+
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after constant declaration"),
+        )?;
+
+        Ok(Statement::Const { name, initializer })
+    }
+
+    fn import_declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        let keyword = self
+            .cursor
+            .previous()
+            .cloned()
+            .ok_or_else(|| self.cursor.error(ParseErrorKind::UnexpectedExpr))?;
+
+        let Some(crate::token::Literal::String(path)) = self
+            .cursor
+            .consume(TokenKind::String, ParseErrorKind::Expected("import path"))?
+            .literal
+            .clone()
+        else {
+            return Err(self.cursor.error(ParseErrorKind::Expected("import path")));
+        };
+
+        // `as` is only special here, so it stays usable as a variable name.
+        let alias = if self
+            .cursor
+            .peek()
+            .is_some_and(|token| token.kind == TokenKind::Identifier && token.lexeme == "as")
+        {
+            self.cursor.advance();
+            Some(self.variable_name("module name")?)
+        } else {
+            None
+        };
+
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after import"),
+        )?;
+
+        Ok(Statement::Import {
+            keyword,
+            path,
+            alias,
+        })
+    }
+
+    fn static_assert_declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        let keyword = self
+            .cursor
+            .previous()
+            .cloned()
+            .ok_or_else(|| self.cursor.error(ParseErrorKind::UnexpectedExpr))?;
+
+        self.cursor.consume(
+            TokenKind::LeftParen,
+            ParseErrorKind::Expected("'(' after 'static_assert'"),
+        )?;
+        let condition = self.expression()?;
+        self.cursor.consume(
+            TokenKind::RightParen,
+            ParseErrorKind::Expected("')' after static_assert condition"),
+        )?;
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after static_assert"),
+        )?;
+
+        Ok(Statement::StaticAssert { keyword, condition })
+    }
+
+    fn variable_name(&mut self, expected: &'static str) -> Result<Token<'a>, ParseError> {
+        if let Some(token) = self.cursor.peek()
+            && KEYWORDS.contains_key(token.lexeme)
+        {
+            return Err(ParseError::new(
+                ParseErrorKind::KeywordAsVariable(token.lexeme.to_string()),
+                ErrorToken::from(token),
+            ));
+        }
+
+        Ok(self
+            .cursor
+            .consume(TokenKind::Identifier, ParseErrorKind::Expected(expected))?
+            .clone())
+    }
+
+    fn statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        if self.cursor.match_token(TokenKind::Print) {
+            return self.print_statement();
+        }
+
+        if self.cursor.match_token(TokenKind::If) {
+            return self.if_statement();
+        }
+
+        if self.cursor.match_token(TokenKind::While) {
+            return self.while_statement();
+        }
+
+        if self.cursor.match_token(TokenKind::For) {
+            return self.for_statement();
+        }
+
+        if self.cursor.match_token(TokenKind::Return) {
+            return self.return_statement();
+        }
+
+        if self.cursor.match_token(TokenKind::Break) {
+            return self.loop_jump(Statement::Break, "break", "';' after 'break'");
+        }
+
+        if self.cursor.match_token(TokenKind::Continue) {
+            return self.loop_jump(Statement::Continue, "continue", "';' after 'continue'");
+        }
+
+        if self.cursor.match_token(TokenKind::LeftBrace) {
+            return Ok(Statement::Block(self.block()?));
+        }
+
+        self.expression_statement()
+    }
+
+    fn print_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let keyword = self
+            .cursor
+            .previous()
+            .cloned()
+            .ok_or_else(|| self.cursor.error(ParseErrorKind::UnexpectedExpr))?;
+
+        let mut values = vec![self.expression()?];
+        while self.cursor.match_token(TokenKind::Comma) {
+            values.push(self.expression()?);
+        }
+
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after value"),
+        )?;
+
+        Ok(Statement::Print { keyword, values })
+    }
+
+    fn return_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let keyword = self
+            .cursor
+            .previous()
+            .cloned()
+            .ok_or_else(|| self.cursor.error(ParseErrorKind::UnexpectedExpr))?;
+
+        if self.function_depth == 0 {
+            return Err(ParseError::new(
+                ParseErrorKind::TopLevelReturn,
+                ErrorToken::from(&keyword),
+            ));
+        }
+
+        let value = if self.cursor.check_token(&TokenKind::Semicolon) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after return value"),
+        )?;
+
+        Ok(Statement::Return { keyword, value })
+    }
+
+    fn if_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        self.cursor.consume(
+            TokenKind::LeftParen,
+            ParseErrorKind::Expected("'(' after 'if'"),
+        )?;
+        let condition = self.condition()?;
+        self.cursor.consume(
+            TokenKind::RightParen,
+            ParseErrorKind::Expected("')' after if condition"),
+        )?;
+
+        let then_branch = Box::new(self.statement()?);
+        let else_branch = if self.cursor.match_token(TokenKind::Else) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Statement::If {
+            condition,
+            then_branch,
+            else_branch,
+        })
+    }
+
+    fn loop_jump(
+        &mut self,
+        statement: Statement<'a>,
+        keyword: &'static str,
+        semicolon: &'static str,
+    ) -> Result<Statement<'a>, ParseError> {
+        let error_token = self
+            .cursor
+            .previous()
+            .map_or_else(ErrorToken::default, ErrorToken::from);
+
+        if self.loop_depth == 0 {
+            return Err(ParseError::new(
+                ParseErrorKind::OutsideLoop(keyword),
+                error_token,
+            ));
+        }
+
+        self.cursor
+            .consume(TokenKind::Semicolon, ParseErrorKind::Expected(semicolon))?;
+
+        Ok(statement)
+    }
+
+    fn loop_body(&mut self) -> Result<Statement<'a>, ParseError> {
+        self.loop_depth += 1;
+        let body = self.statement();
+        self.loop_depth -= 1;
+
+        body
+    }
+
+    fn condition(&mut self) -> Result<Expr<'a>, ParseError> {
+        let condition = self.expression()?;
+
+        if let Expr::Assignment { name, .. } = &condition
+            && self.condition_warnings
+        {
+            self.warnings.push(format!(
+                "[line {}] Warning: Assignment to '{}' used as a condition; did you mean '=='?",
+                name.line, name.lexeme
+            ));
+        }
+
+        Ok(condition)
+    }
+
+    fn while_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        self.cursor.consume(
+            TokenKind::LeftParen,
+            ParseErrorKind::Expected("'(' after 'while'"),
+        )?;
+        let condition = self.condition()?;
+        self.cursor.consume(
+            TokenKind::RightParen,
+            ParseErrorKind::Expected("')' after condition"),
+        )?;
+
+        let body = Box::new(self.loop_body()?);
+
+        Ok(Statement::While {
+            condition,
+            body,
+            increment: None,
+        })
+    }
+
+    fn for_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        self.cursor.consume(
+            TokenKind::LeftParen,
+            ParseErrorKind::Expected("'(' after 'for'"),
+        )?;
+
+        let initializer = if self.cursor.match_token(TokenKind::Semicolon) {
+            None
+        } else if self.cursor.match_token(TokenKind::Var) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.cursor.check_token(&TokenKind::Semicolon) {
+            Expr::Literal(Literal::Boolean(true))
+        } else {
+            self.expression()?
+        };
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after loop condition"),
+        )?;
+
+        let increment = if self.cursor.check_token(&TokenKind::RightParen) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.cursor.consume(
+            TokenKind::RightParen,
+            ParseErrorKind::Expected("')' after for clauses"),
+        )?;
+
+        let mut body = Statement::While {
+            condition,
+            body: Box::new(self.loop_body()?),
+            increment,
+        };
+
+        if let Some(initializer) = initializer {
+            body = Statement::Block(vec![initializer, body]);
         }
+
+        Ok(body)
+    }
+
+    /// Expects the opening `{` to have been consumed already.
+    fn block(&mut self) -> Result<Vec<Statement<'a>>, ParseError> {
+        let opened_at = self.cursor.previous().map_or(0, |brace| brace.line);
+        let mut statements = Vec::new();
+
+        while !self.cursor.check_token(&TokenKind::RightBrace) && !self.is_complete() {
+            if let Some(statement) = self.recovering_declaration() {
+                statements.push(statement);
+            }
+        }
+
+        if self.is_complete() {
+            return Err(self
+                .cursor
+                .error(ParseErrorKind::UnterminatedBlock(opened_at)));
+        }
+
+        self.cursor.consume(
+            TokenKind::RightBrace,
+            ParseErrorKind::Expected("'}' after block"),
+        )?;
+
+        Ok(statements)
+    }
+
+    fn expression_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let expr = self.comma()?;
+        self.cursor.consume(
+            TokenKind::Semicolon,
+            ParseErrorKind::Expected("';' after expression"),
+        )?;
+
+        Ok(Statement::Expression(expr))
     }
 
     pub fn expression(&mut self) -> Result<Expr<'a>, ParseError> {
-        self.primary()
+        self.assignment()
+    }
+
+    /// Only used where a comma can't also mean "next argument" or "next
+    /// value": inside parentheses and in expression statements.
+    fn comma(&mut self) -> Result<Expr<'a>, ParseError> {
+        let first = self.expression()?;
+
+        if !self.cursor.check_token(&TokenKind::Comma) {
+            return Ok(first);
+        }
+
+        let mut expressions = vec![first];
+        while self.cursor.match_token(TokenKind::Comma) {
+            expressions.push(self.expression()?);
+        }
+
+        Ok(Expr::Comma(expressions))
+    }
+
+    fn assignment(&mut self) -> Result<Expr<'a>, ParseError> {
+        let mut expr = self.ternary()?;
+
+        if self.cursor.match_token(TokenKind::Equal) {
+            let equals = self
+                .cursor
+                .previous()
+                .map_or_else(ErrorToken::default, ErrorToken::from);
+            let value = self.assignment()?;
+
+            match &mut expr {
+                Expr::Variable { name, .. } => {
+                    return Ok(Expr::Assignment {
+                        id: next_expr_id(),
+                        name: name.clone(),
+                        value: Box::new(value),
+                    });
+                }
+                Expr::Get { object, name } => {
+                    return Ok(Expr::Set {
+                        object: std::mem::take(object),
+                        name: name.clone(),
+                        value: Box::new(value),
+                    });
+                }
+                Expr::Index {
+                    target,
+                    bracket,
+                    index,
+                } => {
+                    return Ok(Expr::IndexAssignment {
+                        target: std::mem::take(target),
+                        bracket: bracket.clone(),
+                        index: std::mem::take(index),
+                        value: Box::new(value),
+                    });
+                }
+                _ => {}
+            }
+
+            return Err(ParseError::new(
+                ParseErrorKind::InvalidAssignmentTarget,
+                equals,
+            ));
+        }
+
+        Ok(expr)
+    }
+
+    fn ternary(&mut self) -> Result<Expr<'a>, ParseError> {
+        let condition = self.logical_or()?;
+
+        if !self.cursor.match_token(TokenKind::Question) {
+            return Ok(condition);
+        }
+
+        let then_branch = self.expression()?;
+        self.cursor.consume(
+            TokenKind::Colon,
+            ParseErrorKind::Expected("':' after then branch of conditional expression"),
+        )?;
+        let else_branch = self.ternary()?;
+
+        Ok(Expr::Ternary {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch: Box::new(else_branch),
+        })
+    }
+
+    fn logical_or(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.logical(TokenKind::Or, Self::logical_and)
+    }
+
+    fn logical_and(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.logical(TokenKind::And, Self::equality)
+    }
+
+    fn logical(
+        &mut self,
+        kind: TokenKind,
+        operand: fn(&mut Self) -> Result<Expr<'a>, ParseError>,
+    ) -> Result<Expr<'a>, ParseError> {
+        let mut expr = operand(self)?;
+
+        while self.cursor.match_token(kind) {
+            let (operator, _) = self.previous_operator()?;
+            let right_operand = operand(self)?;
+
+            expr = Expr::Logical {
+                left_operand: Box::new(expr),
+                operator,
+                right_operand: Box::new(right_operand),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn equality(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(&[TokenKind::BangEqual, TokenKind::EqualEqual], Self::bit_or)
+    }
+
+    fn bit_or(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(&[TokenKind::Pipe], Self::bit_xor)
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(&[TokenKind::Caret], Self::bit_and)
+    }
+
+    fn bit_and(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(&[TokenKind::Ampersand], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(
+            &[
+                TokenKind::Greater,
+                TokenKind::GreaterEqual,
+                TokenKind::Less,
+                TokenKind::LessEqual,
+            ],
+            Self::term,
+        )
+    }
+
+    fn term(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(&[TokenKind::Minus, TokenKind::Plus], Self::factor)
+    }
+
+    fn factor(&mut self) -> Result<Expr<'a>, ParseError> {
+        self.binary(
+            &[TokenKind::Slash, TokenKind::Star, TokenKind::Percent],
+            Self::unary,
+        )
+    }
+
+    fn binary(
+        &mut self,
+        kinds: &[TokenKind],
+        operand: fn(&mut Self) -> Result<Expr<'a>, ParseError>,
+    ) -> Result<Expr<'a>, ParseError> {
+        let mut expr = operand(self)?;
+
+        while self.cursor.match_tokens(kinds) {
+            let (operator, line) = self.previous_operator()?;
+            let right_operand = operand(self)?;
+
+            expr = Expr::Binary {
+                left_operand: Box::new(expr),
+                operator,
+                right_operand: Box::new(right_operand),
+                line,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr<'a>, ParseError> {
+        if self
+            .cursor
+            .match_tokens(&[TokenKind::Bang, TokenKind::Minus])
+        {
+            let (operator, line) = self.previous_operator()?;
+            let operand = self.unary()?;
+
+            return Ok(Expr::Unary {
+                operator,
+                operand: Box::new(operand),
+                line,
+            });
+        }
+
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr<'a>, ParseError> {
+        let expr = self.postfix()?;
+
+        if self.cursor.match_token(TokenKind::StarStar) {
+            let (operator, line) = self.previous_operator()?;
+            let right_operand = self.unary()?;
+
+            return Ok(Expr::Binary {
+                left_operand: Box::new(expr),
+                operator,
+                right_operand: Box::new(right_operand),
+                line,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> Result<Expr<'a>, ParseError> {
+        let expr = self.call()?;
+
+        if !self
+            .cursor
+            .match_tokens(&[TokenKind::PlusPlus, TokenKind::MinusMinus])
+        {
+            return Ok(expr);
+        }
+
+        let Some(token) = self.cursor.previous() else {
+            return Err(self.cursor.error(ParseErrorKind::UnexpectedExpr));
+        };
+        let operator = if token.kind == TokenKind::PlusPlus {
+            Operator::Add
+        } else {
+            Operator::Subtract
+        };
+
+        match expr {
+            Expr::Variable { ref name, .. } => Ok(Expr::Postfix {
+                id: next_expr_id(),
+                name: name.clone(),
+                operator,
+            }),
+            _ => Err(ParseError::new(
+                ParseErrorKind::InvalidIncrementTarget,
+                ErrorToken::from(token),
+            )),
+        }
+    }
+
+    fn call(&mut self) -> Result<Expr<'a>, ParseError> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.cursor.match_token(TokenKind::LeftParen) {
+                expr = self.finish_call(expr)?;
+            } else if self.cursor.match_token(TokenKind::LeftBracket) {
+                expr = self.finish_index(expr)?;
+            } else if self.cursor.match_token(TokenKind::Dot) {
+                let name = self
+                    .cursor
+                    .consume(
+                        TokenKind::Identifier,
+                        ParseErrorKind::Expected("property name after '.'"),
+                    )?
+                    .clone();
+
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name,
+                };
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
     }
 
-    // fn unary(&mut self) -> Result<Expr<'a>, ParseError> {
-    //     let expr = self.primary()?;
-    //     todo!()
-    // }
+    fn finish_call(&mut self, callee: Expr<'a>) -> Result<Expr<'a>, ParseError> {
+        let mut arguments = Vec::new();
+
+        if !self.cursor.check_token(&TokenKind::RightParen) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    self.errors
+                        .push(self.cursor.error(ParseErrorKind::TooManyArguments));
+                }
+                arguments.push(self.expression()?);
+
+                if !self.cursor.match_token(TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+
+        let paren = self
+            .cursor
+            .consume(
+                TokenKind::RightParen,
+                ParseErrorKind::Expected("')' after arguments"),
+            )?
+            .clone();
+
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+        })
+    }
+
+    fn previous_operator(&self) -> Result<(Operator, usize), ParseError> {
+        self.cursor
+            .previous()
+            .and_then(|token| Operator::from_token_kind(token.kind).map(|op| (op, token.line)))
+            .ok_or_else(|| self.cursor.error(ParseErrorKind::UnexpectedExpr))
+    }
+
+    fn finish_index(&mut self, target: Expr<'a>) -> Result<Expr<'a>, ParseError> {
+        let index = self.expression()?;
+
+        let bracket = self
+            .cursor
+            .consume(
+                TokenKind::RightBracket,
+                ParseErrorKind::Expected("']' after index"),
+            )?
+            .clone();
+
+        Ok(Expr::Index {
+            target: Box::new(target),
+            bracket,
+            index: Box::new(index),
+        })
+    }
 
     fn primary(&mut self) -> Result<Expr<'a>, ParseError> {
         if self.cursor.match_token(TokenKind::True) {
@@ -47,45 +1011,148 @@ impl<'a> Parser<'a> {
         }
 
         if self.cursor.match_token(TokenKind::String)
+            && let Some(crate::token::Literal::String(string)) = self
+                .cursor
+                .previous()
+                .and_then(|token| token.literal.as_ref())
+        {
+            return Ok(Expr::Literal(Literal::String(string.clone())));
+        }
+
+        if self.cursor.match_token(TokenKind::Fun) {
+            let keyword = self
+                .cursor
+                .previous()
+                .cloned()
+                .ok_or_else(|| self.cursor.error(ParseErrorKind::UnexpectedExpr))?;
+            return Ok(Expr::Lambda(self.function_body(keyword)?));
+        }
+
+        if self.cursor.match_token(TokenKind::ByteString)
+            && let Some(crate::token::Literal::String(string)) = self
+                .cursor
+                .previous()
+                .and_then(|token| token.literal.as_ref())
+        {
+            let bytes = string
+                .bytes()
+                .map(|byte| Expr::Literal(Literal::Number(f64::from(byte))))
+                .collect();
+            return Ok(Expr::ListLiteral(bytes));
+        }
+
+        if self.cursor.match_token(TokenKind::Super)
+            && let Some(keyword) = self.cursor.previous()
+        {
+            self.cursor.consume(
+                TokenKind::Dot,
+                ParseErrorKind::Expected("'.' after 'super'"),
+            )?;
+            let method = self
+                .cursor
+                .consume(
+                    TokenKind::Identifier,
+                    ParseErrorKind::Expected("superclass method name"),
+                )?
+                .clone();
+
+            return Ok(Expr::Super {
+                id: next_expr_id(),
+                keyword: keyword.clone(),
+                method,
+            });
+        }
+
+        if self.cursor.match_token(TokenKind::This)
+            && let Some(token) = self.cursor.previous()
+        {
+            return Ok(Expr::This {
+                id: next_expr_id(),
+                keyword: token.clone(),
+            });
+        }
+
+        if self.cursor.match_token(TokenKind::Identifier)
             && let Some(token) = self.cursor.previous()
         {
-            return Ok(Expr::Literal(Literal::String(
-                &token.lexeme[1..token.lexeme.len() - 1],
-            )));
+            return Ok(Expr::Variable {
+                id: next_expr_id(),
+                name: token.clone(),
+            });
+        }
+
+        if self.cursor.match_token(TokenKind::LeftBracket) {
+            let mut elements = Vec::new();
+
+            if !self.cursor.check_token(&TokenKind::RightBracket) {
+                loop {
+                    elements.push(self.expression()?);
+
+                    if !self.cursor.match_token(TokenKind::Comma) {
+                        break;
+                    }
+                }
+            }
+
+            self.cursor.consume(
+                TokenKind::RightBracket,
+                ParseErrorKind::Expected("']' after list elements"),
+            )?;
+            return Ok(Expr::ListLiteral(elements));
         }
 
         if self.cursor.match_token(TokenKind::LeftParen) {
-            let expr = self.expression()?;
-            self.cursor.consume(TokenKind::RightParen)?;
+            let expr = self.comma()?;
+            self.cursor
+                .consume(TokenKind::RightParen, ParseErrorKind::UnmatchedParentheses)?;
             return Ok(Expr::Grouping(Box::new(expr)));
         }
 
-        Err(ParseError::UnexpectedExpr)
+        Err(self.cursor.error(ParseErrorKind::UnexpectedExpr))
     }
 }
 
-pub struct ParserCursor<'a> {
-    tokens: &'a [Token<'a>],
+/// Everything `parse_program` found, for tools that want to keep going past
+/// errors, such as editor integrations.
+#[derive(Debug)]
+pub struct Program<'a> {
+    pub statements: Vec<Statement<'a>>,
+    pub errors: Vec<ParseError>,
+    /// Includes the EOF token.
+    pub token_count: usize,
+    pub line_count: usize,
+    /// Bytes of source, taken from where the EOF token starts.
+    pub source_len: usize,
+}
+
+pub struct ParserCursor<'t, 'a> {
+    tokens: &'t [Token<'a>],
     position: usize,
 }
 
-impl<'a> ParserCursor<'a> {
-    pub const fn new(tokens: &'a [Token<'a>]) -> Self {
+impl<'t, 'a> ParserCursor<'t, 'a> {
+    pub const fn new(tokens: &'t [Token<'a>]) -> Self {
         Self {
             tokens,
             position: 0,
         }
     }
 
+    pub const fn tokens(&self) -> &'t [Token<'a>] {
+        self.tokens
+    }
+
     #[allow(clippy::unwrap_used)]
-    pub fn consume(&mut self, kind: TokenKind) -> Result<&Token<'a>, ParseError> {
+    pub fn consume(
+        &mut self,
+        kind: TokenKind,
+        error: ParseErrorKind,
+    ) -> Result<&'t Token<'a>, ParseError> {
         if self.check_token(&kind) {
             return Ok(self.advance().unwrap());
         }
 
-        Err(ParseError::UnmatchedParentheses {
-            line: self.peek().map_or(0, |token| token.line),
-        })
+        Err(self.error(error))
     }
 
     pub fn match_token(&mut self, kind: TokenKind) -> bool {
@@ -106,7 +1173,7 @@ impl<'a> ParserCursor<'a> {
         self.peek().is_some_and(|token| token.kind == *kind)
     }
 
-    pub fn advance(&mut self) -> Option<&Token<'a>> {
+    pub fn advance(&mut self) -> Option<&'t Token<'a>> {
         let token = self.tokens.get(self.position);
 
         if token.is_some() {
@@ -116,7 +1183,7 @@ impl<'a> ParserCursor<'a> {
         token
     }
 
-    pub fn previous(&self) -> Option<&Token<'a>> {
+    pub fn previous(&self) -> Option<&'t Token<'a>> {
         self.tokens.get(self.position - 1)
     }
 
@@ -130,15 +1197,374 @@ impl<'a> ParserCursor<'a> {
         )
     }
 
-    pub fn peek(&self) -> Option<&Token<'a>> {
+    pub fn peek(&self) -> Option<&'t Token<'a>> {
         self.tokens.get(self.position)
     }
+
+    pub fn error_token(&self) -> ErrorToken {
+        self.peek()
+            .or_else(|| self.tokens.last())
+            .map_or_else(ErrorToken::default, ErrorToken::from)
+    }
+
+    pub fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(kind, self.error_token())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorToken {
+    pub lexeme: String,
+    pub kind: TokenKind,
+    pub line: usize,
+    pub column: usize,
+    pub at_end: bool,
+}
+
+impl Default for ErrorToken {
+    fn default() -> Self {
+        Self {
+            lexeme: String::new(),
+            kind: TokenKind::EOF,
+            line: 0,
+            column: 0,
+            at_end: true,
+        }
+    }
+}
+
+impl From<&Token<'_>> for ErrorToken {
+    fn from(token: &Token<'_>) -> Self {
+        Self {
+            lexeme: token.lexeme.to_string(),
+            kind: token.kind,
+            line: token.line,
+            column: token.column,
+            at_end: token.kind == TokenKind::EOF,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
-pub enum ParseError {
-    #[error("Error: Expected expression")]
+#[error("[line {}:{}] Error: {kind}", .token.line, .token.column)]
+pub struct ParseError {
+    pub token: ErrorToken,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub const fn new(kind: ParseErrorKind, token: ErrorToken) -> Self {
+        Self { token, kind }
+    }
+
+    pub const fn token(&self) -> &ErrorToken {
+        &self.token
+    }
+
+    pub const fn line(&self) -> usize {
+        self.token.line
+    }
+
+    pub fn line_display(&self) -> String {
+        format!("[line {}] Error: {}", self.token.line, self.kind)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ParseErrorKind {
+    #[error("Expected expression")]
     UnexpectedExpr,
-    #[error("[line {line}] Error: Unmatched parentheses.")]
-    UnmatchedParentheses { line: usize },
+    #[error("Unmatched parentheses.")]
+    UnmatchedParentheses,
+    #[error("Unterminated block opened at line {0}.")]
+    UnterminatedBlock(usize),
+    #[error("Token stream must end with EOF.")]
+    MissingEof,
+    #[error("Unexpected trailing tokens.")]
+    TrailingTokens,
+    #[error("Expect {0}.")]
+    Expected(&'static str),
+    #[error("Cannot use keyword '{0}' as a variable name.")]
+    KeywordAsVariable(String),
+    #[error("Invalid assignment target.")]
+    InvalidAssignmentTarget,
+    #[error("Invalid increment target.")]
+    InvalidIncrementTarget,
+    #[error("Can't return from top-level code.")]
+    TopLevelReturn,
+    #[error("Can't read local variable in its own initializer.")]
+    OwnInitializer,
+    #[error("Already a variable with this name in this scope.")]
+    AlreadyDeclared,
+    #[error("Already a function with this name in this scope.")]
+    DuplicateFunction,
+    #[error("const must be initialized.")]
+    UninitializedConst,
+    #[error("Can't return a value from an initializer.")]
+    ReturnFromInitializer,
+    #[error("Can't use 'this' outside of a class.")]
+    ThisOutsideClass,
+    #[error("Can't use 'super' outside of a class.")]
+    SuperOutsideClass,
+    #[error("Can't use 'super' in a class with no superclass.")]
+    SuperWithoutSuperclass,
+    #[error("A class can't inherit from itself.")]
+    InheritsFromItself,
+    #[error("'{0}' outside loop.")]
+    OutsideLoop(&'static str),
+    #[error("Can't have more than 255 arguments.")]
+    TooManyArguments,
+    #[error("Can't have more than 255 parameters.")]
+    TooManyParameters,
+    #[error("Static assertion failed.")]
+    StaticAssertionFailed,
+    #[error("static_assert requires a constant expression.")]
+    NonConstantStaticAssert,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn parse_expr(source: &str) -> Result<String, ParseError> {
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+        Parser::new(&tokens).parse().map(|expr| expr.to_string())
+    }
+
+    fn parse_program(source: &str) -> Result<Vec<String>, Vec<ParseError>> {
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+        let statements = Parser::new(&tokens).parse_statements()?;
+
+        Ok(statements.iter().map(Statement::to_json).collect())
+    }
+
+    #[test]
+    fn rejects_every_keyword_as_a_variable_name() {
+        for keyword in KEYWORDS.keys() {
+            let source = format!("var {keyword} = 1;");
+            let errors = parse_program(&source).unwrap_err();
+
+            assert_eq!(
+                errors[0].kind.to_string(),
+                format!("Cannot use keyword '{keyword}' as a variable name.")
+            );
+        }
+    }
+
+    #[test]
+    fn displays_method_chains_as_nested_forms() {
+        assert_eq!(parse_expr("a.b.c()").unwrap(), "(call (. (. a b) c))");
+        assert_eq!(
+            parse_expr("a.b(1).c(2, 3)").unwrap(),
+            "(call (. (call (. a b) 1.0) c) 2.0 3.0)"
+        );
+    }
+
+    #[test]
+    fn unterminated_block_reports_the_opening_line() {
+        let errors = parse_program("var a = 1;\n{\n  print a;\n").unwrap_err();
+        assert_eq!(
+            errors[0].kind.to_string(),
+            "Unterminated block opened at line 2."
+        );
+
+        let errors = parse_program("fun f() {\n  {\n  }\n").unwrap_err();
+        assert_eq!(
+            errors[0].kind.to_string(),
+            "Unterminated block opened at line 1."
+        );
+    }
+
+    #[test]
+    fn parse_errors_carry_the_failing_token() {
+        let error = parse_expr("(1 + ;").unwrap_err();
+        assert_eq!(error.token.lexeme, ";");
+        assert_eq!(error.token.kind, TokenKind::Semicolon);
+        assert_eq!((error.token.line, error.token.column), (1, 6));
+        assert!(!error.token.at_end);
+
+        let error = parse_expr("1 +").unwrap_err();
+        assert_eq!(error.token.kind, TokenKind::EOF);
+        assert!(error.token.at_end);
+    }
+
+    fn condition_warnings(source: &str) -> Vec<String> {
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+        let mut parser = Parser::new(&tokens).with_condition_warnings(true);
+        parser.parse_statements().unwrap();
+
+        parser.warnings().to_vec()
+    }
+
+    #[test]
+    fn warns_about_assignment_used_as_a_condition() {
+        assert_eq!(
+            condition_warnings("var x;\nif (x = 5) print x;"),
+            ["[line 2] Warning: Assignment to 'x' used as a condition; did you mean '=='?"]
+        );
+        assert_eq!(condition_warnings("var x; while (x = nil) {}").len(), 1);
+    }
+
+    #[test]
+    fn comparisons_and_grouped_assignments_do_not_warn() {
+        assert!(condition_warnings("var x; if (x == 5) print x;").is_empty());
+        assert!(condition_warnings("var x; if ((x = 5)) print x;").is_empty());
+
+        let tokens = Lexer::new("var x; if (x = 5) print x;")
+            .scan_tokens()
+            .tokens;
+        let mut parser = Parser::new(&tokens);
+        parser.parse_statements().unwrap();
+        assert!(parser.warnings().is_empty());
+    }
+
+    #[test]
+    fn parse_program_keeps_good_statements_around_a_bad_one() {
+        let source = "var a = 1;\nvar = 2;\nprint a;";
+        let tokens = Lexer::new(source).scan_tokens().tokens;
+        let program = Parser::new(&tokens).parse_program();
+
+        assert_eq!(program.statements.len(), 2);
+        assert_eq!(program.errors.len(), 1);
+        assert_eq!(program.errors[0].line(), 2);
+        assert_eq!(program.token_count, tokens.len());
+        assert_eq!(program.line_count, 3);
+        assert_eq!(program.source_len, source.len());
+    }
+
+    #[test]
+    fn or_binds_inside_assignment() {
+        assert_eq!(parse_expr("a or b").unwrap(), "(or a b)");
+        assert_eq!(parse_expr("a = x or y").unwrap(), "(= a (or x y))");
+        assert_eq!(parse_expr("a or b and c").unwrap(), "(or a (and b c))");
+    }
+
+    #[test]
+    fn synchronize_reports_each_bad_statement() {
+        let errors = parse_program("var = 1;\nprint 2;\nprint ;\nvar ok = 3;\n1 +;").unwrap_err();
+        let lines = errors.iter().map(ParseError::line).collect::<Vec<_>>();
+
+        assert_eq!(lines, [1, 3, 5]);
+    }
+
+    #[test]
+    fn ternary_is_right_associative() {
+        assert_eq!(parse_expr("a ? b : c").unwrap(), "(?: a b c)");
+        assert_eq!(
+            parse_expr("a ? b : c ? d : e").unwrap(),
+            "(?: a b (?: c d e))"
+        );
+        assert_eq!(
+            parse_expr("x = a or b ? 1 : 2").unwrap(),
+            "(= x (?: (or a b) 1.0 2.0))"
+        );
+    }
+
+    #[test]
+    fn limits_arguments_and_parameters_to_255() {
+        let arguments = vec!["1"; 256].join(", ");
+        let errors = parse_program(&format!("f({arguments});")).unwrap_err();
+        assert_eq!(
+            errors[0].kind.to_string(),
+            "Can't have more than 255 arguments."
+        );
+        assert!(parse_program(&format!("f({});", vec!["1"; 255].join(", "))).is_ok());
+
+        let parameters = (0..256).map(|i| format!("p{i}")).collect::<Vec<_>>();
+        let errors = parse_program(&format!("fun f({}) {{}}", parameters.join(", "))).unwrap_err();
+        assert_eq!(
+            errors[0].kind.to_string(),
+            "Can't have more than 255 parameters."
+        );
+    }
+
+    #[test]
+    fn number_literals_print_without_exponents() {
+        assert_eq!(parse_expr("123456789.0").unwrap(), "123456789.0");
+        assert_eq!(parse_expr("0.0001").unwrap(), "0.0001");
+        assert_eq!(parse_expr("1.5").unwrap(), "1.5");
+        assert_eq!(parse_expr("1e300").unwrap(), "1e300");
+    }
+
+    #[test]
+    fn parses_property_gets_and_sets() {
+        assert_eq!(parse_expr("a.b.c").unwrap(), "(. (. a b) c)");
+        assert_eq!(parse_expr("a.b = 1").unwrap(), "(= (. a b) 1.0)");
+        assert_eq!(parse_expr("a.b.c = d").unwrap(), "(= (. (. a b) c) d)");
+    }
+
+    #[test]
+    fn power_is_right_associative_and_binds_tighter_than_factor() {
+        assert_eq!(parse_expr("2 ** 3 ** 2").unwrap(), "(** 2.0 (** 3.0 2.0))");
+        assert_eq!(parse_expr("2 * 3 ** 2").unwrap(), "(* 2.0 (** 3.0 2.0))");
+    }
+
+    #[test]
+    fn comma_only_applies_inside_groups() {
+        assert_eq!(
+            parse_expr("(1, 2, 3)").unwrap(),
+            "(group (comma 1.0 2.0 3.0))"
+        );
+        assert_eq!(parse_expr("f(1, 2)").unwrap(), "(call f 1.0 2.0)");
+    }
+
+    #[test]
+    fn parses_import_declarations() {
+        assert_eq!(
+            parse_program(r#"import "lib/util.lox";"#).unwrap(),
+            [r#"{"type":"Import","path":"lib/util.lox","alias":null}"#]
+        );
+
+        let errors = parse_program("import util;").unwrap_err();
+        assert_eq!(errors[0].kind.to_string(), "Expect import path.");
+    }
+
+    #[test]
+    fn parses_namespaced_imports() {
+        assert_eq!(
+            parse_program(r#"import "math.lox" as math;"#).unwrap(),
+            [r#"{"type":"Import","path":"math.lox","alias":"math"}"#]
+        );
+        assert!(parse_program("var as = 1; print as;").is_ok());
+
+        let errors = parse_program(r#"import "math.lox" as;"#).unwrap_err();
+        assert_eq!(errors[0].kind.to_string(), "Expect module name.");
+    }
+
+    #[test]
+    fn byte_strings_parse_as_lists_of_bytes() {
+        assert_eq!(parse_expr(r#"b"AB""#).unwrap(), "(list 65.0 66.0)");
+        assert_eq!(parse_expr(r#"b"""#).unwrap(), "(list)");
+    }
+
+    #[test]
+    fn parses_static_assertions() {
+        assert_eq!(
+            parse_program("static_assert(1 < 2);").unwrap(),
+            [
+                r#"{"type":"StaticAssert","condition":{"type":"Binary","op":"<","left":{"type":"Literal","value":1},"right":{"type":"Literal","value":2}}}"#
+            ]
+        );
+
+        let errors = parse_program("static_assert 1;").unwrap_err();
+        assert_eq!(
+            errors[0].kind.to_string(),
+            "Expect '(' after 'static_assert'."
+        );
+    }
+
+    #[test]
+    fn parses_lambdas_as_call_arguments() {
+        assert_eq!(
+            parse_expr("forEach(xs, fun (x) { print x; })").unwrap(),
+            "(call forEach xs (fun x))"
+        );
+        assert_eq!(
+            parse_program("var f = fun () { return 1; };").unwrap(),
+            [
+                r#"{"type":"Var","name":"f","initializer":{"type":"Lambda","params":[],"body":[{"type":"Return","value":{"type":"Literal","value":1}}]}}"#
+            ]
+        );
+    }
 }
@@ -0,0 +1,78 @@
+use crate::errors::InterpreterError;
+use std::io::{self, Write};
+
+pub trait LineReader {
+    fn read_line(&mut self, prompt: &str) -> Option<String>;
+}
+
+#[derive(Debug, Default)]
+pub struct StdinLineReader;
+
+impl LineReader for StdinLineReader {
+    fn read_line(&mut self, prompt: &str) -> Option<String> {
+        print!("{prompt}");
+        io::stdout().flush().ok()?;
+
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(input),
+        }
+    }
+}
+
+pub struct Repl<R: LineReader> {
+    reader: R,
+}
+
+impl<R: LineReader> Repl<R> {
+    pub const PROMPT: &'static str = "> ";
+
+    pub const fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    pub fn run<F>(&mut self, mut eval: F) -> Result<(), InterpreterError>
+    where
+        F: FnMut(&str) -> Result<(), InterpreterError>,
+    {
+        while let Some(input) = self.reader.read_line(Self::PROMPT) {
+            let line = input.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            eval(line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ScriptedReader(std::vec::IntoIter<&'static str>);
+
+    impl LineReader for ScriptedReader {
+        fn read_line(&mut self, _prompt: &str) -> Option<String> {
+            self.0.next().map(|line| format!("{line}\n"))
+        }
+    }
+
+    #[test]
+    fn feeds_each_non_empty_line_to_eval() {
+        let reader = ScriptedReader(vec!["var x = 1;", "", "print x;"].into_iter());
+        let mut seen = Vec::new();
+
+        Repl::new(reader)
+            .run(|line| {
+                seen.push(line.to_string());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(seen, ["var x = 1;", "print x;"]);
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{
+    grammar::{Expr, ExprId, FunctionDeclaration, Statement},
+    interpreter::Interpreter,
+    parser::{ErrorToken, ParseError, ParseErrorKind},
+    token::Token,
+};
+use std::collections::{HashMap, HashSet};
+
+pub struct Resolver<'i, 'a> {
+    interpreter: &'i mut Interpreter<'a>,
+    scopes: Vec<HashMap<&'a str, bool>>,
+    errors: Vec<ParseError>,
+    current_class: ClassType,
+    current_function: FunctionType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FunctionType {
+    None,
+    Function,
+    Method,
+    Initializer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+impl<'i, 'a> Resolver<'i, 'a> {
+    pub const fn new(interpreter: &'i mut Interpreter<'a>) -> Self {
+        Self {
+            interpreter,
+            scopes: Vec::new(),
+            errors: Vec::new(),
+            current_class: ClassType::None,
+            current_function: FunctionType::None,
+        }
+    }
+
+    pub fn resolve(mut self, statements: &[Statement<'a>]) -> Result<(), Vec<ParseError>> {
+        self.resolve_statements(statements);
+
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    /// Declares every function in `statements` before resolving any of
+    /// them, matching the interpreter's hoisting. Hoisting would let a later
+    /// declaration replace an earlier one before either runs, so two
+    /// functions with the same name in one scope are an error, even globally.
+    fn resolve_statements(&mut self, statements: &[Statement<'a>]) {
+        let mut functions = HashSet::new();
+
+        for statement in statements {
+            if let Statement::Function(declaration) = statement {
+                if !functions.insert(declaration.name.lexeme) {
+                    self.errors.push(ParseError::new(
+                        ParseErrorKind::DuplicateFunction,
+                        ErrorToken::from(&declaration.name),
+                    ));
+                    continue;
+                }
+
+                self.declare(&declaration.name);
+                self.define(&declaration.name);
+            }
+        }
+
+        for statement in statements {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &Statement<'a>) {
+        match statement {
+            Statement::Expression(expr) => self.resolve_expr(expr),
+            Statement::Print { values, .. } => {
+                for value in values {
+                    self.resolve_expr(value);
+                }
+            }
+            Statement::Var { name, initializer } => {
+                self.declare(name);
+                if let Some(initializer) = initializer {
+                    self.resolve_expr(initializer);
+                }
+                self.define(name);
+            }
+            Statement::Const { name, initializer } => {
+                self.declare(name);
+                self.resolve_expr(initializer);
+                self.define(name);
+            }
+            Statement::Block(statements) => {
+                self.begin_scope();
+                self.resolve_statements(statements);
+                self.end_scope();
+            }
+            Statement::Function(declaration) => {
+                self.resolve_function(declaration, FunctionType::Function);
+            }
+            Statement::Class {
+                name,
+                superclass,
+                methods,
+            } => {
+                let enclosing_class = std::mem::replace(&mut self.current_class, ClassType::Class);
+
+                self.declare(name);
+                self.define(name);
+
+                if let Some(superclass) = superclass {
+                    if let Expr::Variable {
+                        name: superclass_name,
+                        ..
+                    } = superclass
+                        && superclass_name.lexeme == name.lexeme
+                    {
+                        self.errors.push(ParseError::new(
+                            ParseErrorKind::InheritsFromItself,
+                            ErrorToken::from(superclass_name),
+                        ));
+                    }
+
+                    self.current_class = ClassType::Subclass;
+                    self.resolve_expr(superclass);
+
+                    self.begin_scope();
+                    if let Some(scope) = self.scopes.last_mut() {
+                        scope.insert("super", true);
+                    }
+                }
+
+                self.begin_scope();
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.insert("this", true);
+                }
+
+                for method in methods {
+                    let kind = if method.name.lexeme == "init" {
+                        FunctionType::Initializer
+                    } else {
+                        FunctionType::Method
+                    };
+                    self.resolve_function(method, kind);
+                }
+
+                self.end_scope();
+                if superclass.is_some() {
+                    self.end_scope();
+                }
+
+                self.current_class = enclosing_class;
+            }
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_statement(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.resolve_statement(else_branch);
+                }
+            }
+            Statement::While {
+                condition,
+                body,
+                increment,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_statement(body);
+                if let Some(increment) = increment {
+                    self.resolve_expr(increment);
+                }
+            }
+            // Imported files are resolved on their own when they run; only a
+            // namespace alias is declared where the import appears.
+            Statement::Import { alias, .. } => {
+                if let Some(alias) = alias {
+                    self.declare(alias);
+                    self.define(alias);
+                }
+            }
+            Statement::StaticAssert { keyword, condition } => {
+                self.check_static_assert(keyword, condition);
+            }
+            Statement::Break | Statement::Continue => {}
+            Statement::Return { keyword, value } => {
+                if let Some(value) = value {
+                    if self.current_function == FunctionType::Initializer {
+                        self.errors.push(ParseError::new(
+                            ParseErrorKind::ReturnFromInitializer,
+                            ErrorToken::from(keyword),
+                        ));
+                    }
+
+                    self.resolve_expr(value);
+                }
+            }
+        }
+    }
+
+    /// Folds the condition with the interpreter, which needs no variables
+    /// for a constant expression. One that fails to evaluate, like `1 / 0`,
+    /// doesn't have a constant value either.
+    fn check_static_assert(&mut self, keyword: &Token<'a>, condition: &Expr<'a>) {
+        let kind = match condition
+            .is_constant()
+            .then(|| self.interpreter.evaluate(condition))
+        {
+            Some(Ok(value)) if value.is_truthy() => return,
+            Some(Ok(_)) => ParseErrorKind::StaticAssertionFailed,
+            Some(Err(_)) | None => ParseErrorKind::NonConstantStaticAssert,
+        };
+
+        self.errors
+            .push(ParseError::new(kind, ErrorToken::from(keyword)));
+    }
+
+    fn resolve_function(&mut self, declaration: &FunctionDeclaration<'a>, kind: FunctionType) {
+        let enclosing_function = std::mem::replace(&mut self.current_function, kind);
+
+        self.begin_scope();
+        for param in &declaration.params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve_statements(&declaration.body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    /// Walks operator expressions with an explicit stack, left to right, so
+    /// a deeply nested chain like `1 - 1 - ... - 1` can't overflow the Rust
+    /// stack. Everything else goes through `resolve_operand`.
+    fn resolve_expr(&mut self, expr: &Expr<'a>) {
+        let mut pending = vec![expr];
+
+        while let Some(expr) = pending.pop() {
+            match expr {
+                Expr::Grouping(expr) | Expr::Unary { operand: expr, .. } => pending.push(expr),
+                Expr::Binary {
+                    left_operand,
+                    right_operand,
+                    ..
+                }
+                | Expr::Logical {
+                    left_operand,
+                    right_operand,
+                    ..
+                } => pending.extend([&**right_operand, &**left_operand]),
+                Expr::Ternary {
+                    condition,
+                    then_branch,
+                    else_branch,
+                } => pending.extend([&**else_branch, &**then_branch, &**condition]),
+                _ => self.resolve_operand(expr),
+            }
+        }
+    }
+
+    fn resolve_operand(&mut self, expr: &Expr<'a>) {
+        match expr {
+            // Operators are walked by `resolve_expr`; literals need nothing.
+            Expr::Literal(_)
+            | Expr::Grouping(_)
+            | Expr::Unary { .. }
+            | Expr::Binary { .. }
+            | Expr::Logical { .. }
+            | Expr::Ternary { .. } => {}
+            Expr::Variable { id, name } => {
+                if self
+                    .scopes
+                    .last()
+                    .is_some_and(|scope| scope.get(name.lexeme) == Some(&false))
+                {
+                    self.errors.push(ParseError::new(
+                        ParseErrorKind::OwnInitializer,
+                        ErrorToken::from(name),
+                    ));
+                }
+
+                self.resolve_local(*id, name);
+            }
+            Expr::Assignment { id, name, value } => {
+                self.resolve_expr(value);
+                self.resolve_local(*id, name);
+            }
+            Expr::Postfix { id, name, .. } => self.resolve_local(*id, name),
+            Expr::Call {
+                callee, arguments, ..
+            } => {
+                self.resolve_expr(callee);
+                for argument in arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            Expr::This { id, keyword } => {
+                if self.current_class == ClassType::None {
+                    self.errors.push(ParseError::new(
+                        ParseErrorKind::ThisOutsideClass,
+                        ErrorToken::from(keyword),
+                    ));
+                    return;
+                }
+
+                self.resolve_local(*id, keyword);
+            }
+            Expr::Super { id, keyword, .. } => {
+                let kind = match self.current_class {
+                    ClassType::Subclass => {
+                        self.resolve_local(*id, keyword);
+                        return;
+                    }
+                    ClassType::Class => ParseErrorKind::SuperWithoutSuperclass,
+                    ClassType::None => ParseErrorKind::SuperOutsideClass,
+                };
+
+                self.errors
+                    .push(ParseError::new(kind, ErrorToken::from(keyword)));
+            }
+            Expr::Get { object, .. } => self.resolve_expr(object),
+            Expr::Lambda(declaration) => {
+                self.resolve_function(declaration, FunctionType::Function);
+            }
+            Expr::Set { object, value, .. } => {
+                self.resolve_expr(value);
+                self.resolve_expr(object);
+            }
+            Expr::ListLiteral(elements) | Expr::Comma(elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            }
+            Expr::Index { target, index, .. } => {
+                self.resolve_expr(target);
+                self.resolve_expr(index);
+            }
+            Expr::IndexAssignment {
+                target,
+                index,
+                value,
+                ..
+            } => {
+                self.resolve_expr(target);
+                self.resolve_expr(index);
+                self.resolve_expr(value);
+            }
+        }
+    }
+
+    fn resolve_local(&mut self, id: ExprId, name: &Token<'a>) {
+        if let Some(depth) = self
+            .scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(name.lexeme))
+        {
+            self.interpreter.resolve(id, depth);
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token<'a>) {
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+
+        if scope.insert(name.lexeme, false).is_some() {
+            self.errors.push(ParseError::new(
+                ParseErrorKind::AlreadyDeclared,
+                ErrorToken::from(name),
+            ));
+        }
+    }
+
+    fn define(&mut self, name: &Token<'a>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme, true);
+        }
+    }
+}
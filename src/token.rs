@@ -1,4 +1,6 @@
+use crate::value::format_literal_number;
 use phf::phf_map;
+use std::borrow::Cow;
 use std::fmt;
 
 #[derive(Debug, Clone)]
@@ -7,6 +9,11 @@ pub struct Token<'a> {
     pub lexeme: &'a str,
     pub literal: Option<Literal<'a>>,
     pub line: usize,
+    pub column: usize,
+    /// Byte offset of the lexeme in the source; 0 for tokens built without
+    /// the lexer.
+    pub offset: usize,
+    pub normalized_lexeme: Option<String>,
 }
 
 impl<'a> Token<'a> {
@@ -15,21 +22,39 @@ impl<'a> Token<'a> {
         lexeme: &'a str,
         literal: Option<Literal<'a>>,
         line: usize,
+        column: usize,
     ) -> Self {
         Self {
             kind,
             lexeme,
             literal,
             line,
+            column,
+            offset: 0,
+            normalized_lexeme: None,
         }
     }
+
+    pub const fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_normalized_lexeme(mut self, lexeme: String) -> Self {
+        self.normalized_lexeme = Some(lexeme);
+        self
+    }
+
+    pub fn display_lexeme(&self) -> &str {
+        self.normalized_lexeme.as_deref().unwrap_or(self.lexeme)
+    }
 }
 
 impl fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.literal {
-            Some(literal) => write!(f, "{} {} {}", self.kind, self.lexeme, literal),
-            None => write!(f, "{} {} null", self.kind, self.lexeme),
+            Some(literal) => write!(f, "{} {} {}", self.kind, self.display_lexeme(), literal),
+            None => write!(f, "{} {} null", self.kind, self.display_lexeme()),
         }
     }
 }
@@ -40,14 +65,25 @@ pub enum TokenKind {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
 
     Dot,
     Comma,
     Minus,
+    MinusMinus,
     Plus,
+    PlusPlus,
     Semicolon,
     Slash,
     Star,
+    StarStar,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Question,
+    Colon,
 
     Bang,
     Equal,
@@ -59,21 +95,27 @@ pub enum TokenKind {
     GreaterEqual,
 
     String,
+    ByteString,
     Number,
 
     Identifier,
 
     And,
+    Break,
     Class,
+    Const,
+    Continue,
     Else,
     False,
     For,
     Fun,
     If,
+    Import,
     Nil,
     Or,
     Print,
     Return,
+    StaticAssert,
     Super,
     This,
     True,
@@ -90,14 +132,25 @@ impl fmt::Display for TokenKind {
             Self::RightParen => "RIGHT_PAREN",
             Self::LeftBrace => "LEFT_BRACE",
             Self::RightBrace => "RIGHT_BRACE",
+            Self::LeftBracket => "LEFT_BRACKET",
+            Self::RightBracket => "RIGHT_BRACKET",
 
             Self::Dot => "DOT",
             Self::Comma => "COMMA",
             Self::Minus => "MINUS",
+            Self::MinusMinus => "MINUS_MINUS",
             Self::Plus => "PLUS",
+            Self::PlusPlus => "PLUS_PLUS",
             Self::Semicolon => "SEMICOLON",
             Self::Slash => "SLASH",
             Self::Star => "STAR",
+            Self::StarStar => "STAR_STAR",
+            Self::Percent => "PERCENT",
+            Self::Ampersand => "AMPERSAND",
+            Self::Pipe => "PIPE",
+            Self::Caret => "CARET",
+            Self::Question => "QUESTION",
+            Self::Colon => "COLON",
 
             Self::Bang => "BANG",
             Self::Equal => "EQUAL",
@@ -109,21 +162,27 @@ impl fmt::Display for TokenKind {
             Self::GreaterEqual => "GREATER_EQUAL",
 
             Self::String => "STRING",
+            Self::ByteString => "BYTE_STRING",
             Self::Number => "NUMBER",
 
             Self::Identifier => "IDENTIFIER",
 
             Self::And => "AND",
+            Self::Break => "BREAK",
             Self::Class => "CLASS",
+            Self::Const => "CONST",
+            Self::Continue => "CONTINUE",
             Self::Else => "ELSE",
             Self::False => "FALSE",
             Self::For => "FOR",
             Self::Fun => "FUN",
             Self::If => "IF",
+            Self::Import => "IMPORT",
             Self::Nil => "NIL",
             Self::Or => "OR",
             Self::Print => "PRINT",
             Self::Return => "RETURN",
+            Self::StaticAssert => "STATIC_ASSERT",
             Self::Super => "SUPER",
             Self::This => "THIS",
             Self::True => "TRUE",
@@ -140,19 +199,13 @@ impl fmt::Display for TokenKind {
 #[derive(Debug, Clone)]
 pub enum Literal<'a> {
     Number(f64),
-    String(&'a str),
+    String(Cow<'a, str>),
 }
 
 impl fmt::Display for Literal<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Number(n) => {
-                if n.fract() == 0.0 {
-                    write!(f, "{n:.1}")
-                } else {
-                    write!(f, "{n}")
-                }
-            }
+            Self::Number(n) => write!(f, "{}", format_literal_number(*n)),
             Self::String(s) => write!(f, "{s}"),
         }
     }
@@ -160,16 +213,21 @@ impl fmt::Display for Literal<'_> {
 
 pub static KEYWORDS: phf::Map<&'static str, TokenKind> = phf_map! {
     "and" => TokenKind::And,
+    "break" => TokenKind::Break,
     "class" => TokenKind::Class,
+    "const" => TokenKind::Const,
+    "continue" => TokenKind::Continue,
     "else" => TokenKind::Else,
     "false" => TokenKind::False,
     "for" => TokenKind::For,
     "fun" => TokenKind::Fun,
     "if" => TokenKind::If,
+    "import" => TokenKind::Import,
     "nil" => TokenKind::Nil,
     "or" => TokenKind::Or,
     "print" => TokenKind::Print,
     "return" => TokenKind::Return,
+    "static_assert" => TokenKind::StaticAssert,
     "super" => TokenKind::Super,
     "this" => TokenKind::This,
     "true" => TokenKind::True,
@@ -0,0 +1,346 @@
+use crate::{
+    environment::{Environment, EnvironmentRef},
+    grammar::{FunctionDeclaration, Literal},
+    interpreter::{Interpreter, RuntimeError, RuntimeErrorKind},
+    token::{Token, TokenKind},
+};
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+};
+
+#[derive(Debug, Clone)]
+pub enum LiteralValue<'a> {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Nil,
+    Function(Rc<Function<'a>>),
+    NativeFunction(Rc<NativeFunction<'a>>),
+    List(Rc<List<'a>>),
+    Class(Rc<Class<'a>>),
+    Instance(Rc<Instance<'a>>),
+    Module(Rc<Module<'a>>),
+}
+
+impl LiteralValue<'_> {
+    pub const fn is_truthy(&self) -> bool {
+        !matches!(self, Self::Nil | Self::Boolean(false))
+    }
+
+    pub fn is_equal(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Number(l), Self::Number(r)) => l == r,
+            (Self::String(l), Self::String(r)) => l == r,
+            (Self::Boolean(l), Self::Boolean(r)) => l == r,
+            (Self::Nil, Self::Nil) => true,
+            (Self::Function(l), Self::Function(r)) => Rc::ptr_eq(l, r),
+            (Self::NativeFunction(l), Self::NativeFunction(r)) => Rc::ptr_eq(l, r),
+            (Self::List(l), Self::List(r)) => Rc::ptr_eq(l, r),
+            (Self::Class(l), Self::Class(r)) => Rc::ptr_eq(l, r),
+            (Self::Instance(l), Self::Instance(r)) => Rc::ptr_eq(l, r),
+            (Self::Module(l), Self::Module(r)) => Rc::ptr_eq(l, r),
+            _ => false,
+        }
+    }
+
+    /// Like `is_equal`, but numbers within `tolerance` of each other also
+    /// count as equal.
+    pub fn is_equal_within(&self, other: &Self, tolerance: f64) -> bool {
+        match (self, other) {
+            (Self::Number(l), Self::Number(r)) => l == r || (l - r).abs() <= tolerance,
+            _ => self.is_equal(other),
+        }
+    }
+
+    /// Orders two numbers or two strings. Unlike `<`, numbers follow
+    /// `f64::total_cmp`, so NaN sorts consistently instead of comparing
+    /// false against everything.
+    pub fn total_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Number(l), Self::Number(r)) => Some(l.total_cmp(r)),
+            (Self::String(l), Self::String(r)) => Some(l.cmp(r)),
+            _ => None,
+        }
+    }
+
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "number",
+            Self::String(_) => "string",
+            Self::Boolean(_) => "boolean",
+            Self::Nil => "nil",
+            Self::Function(_) | Self::NativeFunction(_) => "function",
+            Self::List(_) => "list",
+            Self::Class(_) => "class",
+            Self::Instance(_) => "instance",
+            Self::Module(_) => "module",
+        }
+    }
+
+    pub fn as_string(&self) -> String {
+        self.as_string_inside(&mut Vec::new())
+    }
+
+    /// `as_string` for a value printed inside the lists in `open`, which are
+    /// still being printed. A list that contains itself prints as `[...]`
+    /// where it recurs.
+    fn as_string_inside(&self, open: &mut Vec<*const List<'_>>) -> String {
+        match self {
+            Self::Number(n) => format_number(*n),
+            Self::String(s) => s.clone(),
+            Self::Boolean(b) => b.to_string(),
+            Self::Nil => "nil".to_string(),
+            Self::Function(function) => function.to_string(),
+            Self::NativeFunction(_) => "<native fn>".to_string(),
+            Self::List(list) => {
+                let pointer = Rc::as_ptr(list).cast();
+                if open.contains(&pointer) {
+                    return "[...]".to_string();
+                }
+
+                open.push(pointer);
+                let elements = list
+                    .elements
+                    .borrow()
+                    .iter()
+                    .map(|element| element.as_string_inside(open))
+                    .collect::<Vec<_>>();
+                open.pop();
+
+                format!("[{}]", elements.join(", "))
+            }
+            Self::Class(class) => class.name.to_string(),
+            Self::Instance(instance) => format!("{} instance", instance.class.name),
+            Self::Module(module) => format!("<module {}>", module.path),
+        }
+    }
+}
+
+/// Lox prints whole numbers without a fractional part and never switches
+/// to exponential notation.
+pub fn format_number(n: f64) -> String {
+    match n {
+        n if n.is_nan() => "NaN".to_string(),
+        f64::INFINITY => "Infinity".to_string(),
+        f64::NEG_INFINITY => "-Infinity".to_string(),
+        n => format!("{n}"),
+    }
+}
+
+/// Number literals in token and AST output always show a fractional part
+/// (`1.0`), except past 1e21 where the digits are mostly rounding noise, so
+/// huge literals like `1e300` keep their exponent instead of spelling out
+/// 301 digits.
+pub fn format_literal_number(n: f64) -> String {
+    match n {
+        n if n.is_finite() && n.abs() >= 1e21 => format!("{n:e}"),
+        n if n.fract() == 0.0 => format!("{n:.1}"),
+        n => format_number(n),
+    }
+}
+
+impl From<&Literal<'_>> for LiteralValue<'_> {
+    fn from(literal: &Literal<'_>) -> Self {
+        match literal {
+            Literal::Number(n) => Self::Number(*n),
+            Literal::String(s) => Self::String((*s).to_string()),
+            Literal::Boolean(b) => Self::Boolean(*b),
+            Literal::Nil => Self::Nil,
+        }
+    }
+}
+
+impl fmt::Display for LiteralValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
+}
+
+pub struct Function<'a> {
+    pub declaration: Rc<FunctionDeclaration<'a>>,
+    pub closure: EnvironmentRef<'a>,
+    pub is_initializer: bool,
+}
+
+impl<'a> Function<'a> {
+    pub fn arity(&self) -> usize {
+        self.declaration.params.len()
+    }
+
+    pub fn bind(&self, instance: Rc<Instance<'a>>) -> Self {
+        let environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        environment
+            .borrow_mut()
+            .define("this", LiteralValue::Instance(instance));
+
+        Self {
+            declaration: Rc::clone(&self.declaration),
+            closure: environment,
+            is_initializer: self.is_initializer,
+        }
+    }
+
+    pub fn bound_this(&self) -> LiteralValue<'a> {
+        self.closure
+            .borrow()
+            .get_own("this")
+            .unwrap_or(LiteralValue::Nil)
+    }
+}
+
+impl fmt::Debug for Function<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.declaration.name.lexeme)
+            .field("arity", &self.arity())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Function<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.declaration.name.kind {
+            TokenKind::Fun => write!(f, "<fn>"),
+            _ => write!(f, "<fn {}>", self.declaration.name.lexeme),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Class<'a> {
+    pub name: &'a str,
+    pub superclass: Option<Rc<Class<'a>>>,
+    pub methods: HashMap<&'a str, Rc<Function<'a>>>,
+}
+
+impl<'a> Class<'a> {
+    pub fn find_method(&self, name: &str) -> Option<Rc<Function<'a>>> {
+        self.methods.get(name).cloned().or_else(|| {
+            self.superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(name))
+        })
+    }
+}
+
+/// The elements behind a list value, shared by every copy of it.
+#[derive(Debug, Default)]
+pub struct List<'a> {
+    pub elements: RefCell<Vec<LiteralValue<'a>>>,
+    frozen: Cell<bool>,
+}
+
+impl<'a> List<'a> {
+    pub const fn new(elements: Vec<LiteralValue<'a>>) -> Self {
+        Self {
+            elements: RefCell::new(elements),
+            frozen: Cell::new(false),
+        }
+    }
+
+    /// Makes assigning an element fail from now on. Shallow: lists inside
+    /// it stay mutable.
+    pub fn freeze(&self) {
+        self.frozen.set(true);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+}
+
+#[derive(Debug)]
+pub struct Instance<'a> {
+    pub class: Rc<Class<'a>>,
+    fields: RefCell<HashMap<String, LiteralValue<'a>>>,
+    frozen: Cell<bool>,
+}
+
+impl<'a> Instance<'a> {
+    pub fn new(class: Rc<Class<'a>>) -> Self {
+        Self {
+            class,
+            fields: RefCell::new(HashMap::new()),
+            frozen: Cell::new(false),
+        }
+    }
+
+    /// Makes setting a field fail from now on. Methods can still be called.
+    pub fn freeze(&self) {
+        self.frozen.set(true);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+
+    pub fn get(self: &Rc<Self>, name: &Token<'_>) -> Result<LiteralValue<'a>, RuntimeError> {
+        if let Some(value) = self.fields.borrow().get(name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        self.class
+            .find_method(name.lexeme)
+            .map(|method| LiteralValue::Function(Rc::new(method.bind(Rc::clone(self)))))
+            .ok_or_else(|| {
+                RuntimeError::new(
+                    RuntimeErrorKind::UndefinedProperty(name.lexeme.to_string()),
+                    name.line,
+                )
+            })
+    }
+
+    pub fn set(&self, name: &Token<'_>, value: LiteralValue<'a>) {
+        self.fields
+            .borrow_mut()
+            .insert(name.lexeme.to_string(), value);
+    }
+}
+
+/// The globals of a file imported with `import "path" as name;`, read
+/// through the alias like an instance's fields.
+#[derive(Debug)]
+pub struct Module<'a> {
+    pub path: String,
+    pub globals: EnvironmentRef<'a>,
+}
+
+impl<'a> Module<'a> {
+    pub fn get(&self, name: &Token<'_>) -> Result<LiteralValue<'a>, RuntimeError> {
+        self.globals.borrow().get_own(name.lexeme).ok_or_else(|| {
+            RuntimeError::new(
+                RuntimeErrorKind::UndefinedProperty(name.lexeme.to_string()),
+                name.line,
+            )
+        })
+    }
+}
+
+pub type NativeFn<'a> =
+    fn(&mut Interpreter<'a>, &[LiteralValue<'a>]) -> Result<LiteralValue<'a>, RuntimeErrorKind>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exact(usize),
+    AtLeast(usize),
+    Between(usize, usize),
+}
+
+pub struct NativeFunction<'a> {
+    pub name: &'static str,
+    pub arity: Arity,
+    pub function: NativeFn<'a>,
+}
+
+impl fmt::Debug for NativeFunction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeFunction")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish_non_exhaustive()
+    }
+}
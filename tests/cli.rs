@@ -0,0 +1,252 @@
+use std::{
+    env, fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+fn interpreter(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn with_stdin(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_codecrafters-interpreter"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// A fresh directory under the system temp dir holding `files`.
+fn scratch_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = env::temp_dir().join(format!("lox-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    dir
+}
+
+#[test]
+fn run_reports_every_compile_error_in_source_order() {
+    let output = interpreter(&["run", "-e", "var a = ;\nprint @;\nvar = 1;"]);
+
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "[line 1:9] Error: Expected expression\n\
+         [line 2:7] Error: Unexpected character: @\n\
+         [line 2:8] Error: Expected expression\n\
+         [line 3:5] Error: Expect variable name.\n\
+         4 errors found.\n"
+    );
+}
+
+#[test]
+fn a_single_error_has_no_summary() {
+    let output = interpreter(&["run", "-e", "print 1"]);
+
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "[line 1:8] Error: Expect ';' after value.\n"
+    );
+}
+
+#[test]
+fn repl_keeps_variables_and_functions_between_lines() {
+    let output = with_stdin(
+        &["repl"],
+        b"var x = 1;\nx + 2\nfun show() { print x; }\n{ class A {} var a = A(); print a; }\nx = 5;\nshow();\nmissing;\nshow();\n",
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).replace("> ", ""),
+        "3\nA instance\n5\n5\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Undefined variable 'missing'.\n[line 1]\n"
+    );
+}
+
+#[test]
+fn dash_reads_the_program_from_stdin() {
+    let output = with_stdin(&["run", "-"], b"print 1+2;\n");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+}
+
+#[test]
+fn unreadable_input_exits_66() {
+    let output = interpreter(&["run", "does/not/exist.lox"]);
+    assert_eq!(output.status.code(), Some(66));
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .starts_with("Failed to read 'does/not/exist.lox': ")
+    );
+
+    let output = with_stdin(&["run", "-"], b"print \"\xFF\";\n");
+    assert_eq!(output.status.code(), Some(66));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Failed to read '-': "));
+}
+
+#[test]
+fn parse_json_prints_an_expression_as_one_object() {
+    let output = interpreter(&["parse", "--json", "-e", "1 + 2"]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        r#"{"type":"Binary","op":"+","left":{"type":"Literal","value":1},"right":{"type":"Literal","value":2}}"#
+            .to_owned()
+            + "\n"
+    );
+}
+
+#[test]
+fn parse_json_prints_a_program_as_an_array_of_statements() {
+    let output = interpreter(&["parse", "--json", "-e", "var a = 1; print a;"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        r#"[{"type":"Var","name":"a","initializer":{"type":"Literal","value":1}},{"type":"Print","expressions":[{"type":"Variable","name":"a"}]}]"#
+            .to_owned()
+            + "\n"
+    );
+
+    let output = interpreter(&["parse", "--json", "-e", "(1 +"]);
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn parse_json_reports_the_program_errors_when_nothing_parses() {
+    let output = interpreter(&["parse", "--json", "-e", "var = 1;\nprint 1"]);
+
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "[line 1:5] Error: Expect variable name.\n[line 2:8] Error: Expect ';' after value.\n"
+    );
+}
+
+#[test]
+fn read_line_reads_stdin_line_by_line() {
+    let source = r#"printf("name? "); print readLine(); print readLine(); print readLine();"#;
+    let output = with_stdin(&["run", "-e", source], b"Ada\r\nGrace\n");
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "name? Ada\nGrace\nnil\n"
+    );
+}
+
+#[test]
+fn import_runs_a_file_relative_to_the_importer_once() {
+    let dir = scratch_dir(
+        "import",
+        &[
+            (
+                "main.lox",
+                "import \"lib/greet.lox\";\nimport \"lib/greet.lox\";\nprint greet(\"Ada\");\n",
+            ),
+            (
+                "lib/greet.lox",
+                "import \"../main.lox\";\nprint \"loading\";\nfun greet(name) { return \"hi \" + name; }\n",
+            ),
+        ],
+    );
+
+    let output = interpreter(&["run", dir.join("main.lox").to_str().unwrap()]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "loading\nhi Ada\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn namespaced_import_keeps_module_globals_apart() {
+    let dir = scratch_dir(
+        "namespace",
+        &[
+            (
+                "main.lox",
+                "import \"math.lox\" as math;\n\
+                 var pi = 3;\n\
+                 print math.pi;\n\
+                 print math.area(1);\n\
+                 print pi, type(math);\n\
+                 print math.missing;\n",
+            ),
+            (
+                "math.lox",
+                "var pi = 3.14159;\nfun area(r) { return pi * r * r; }\n",
+            ),
+        ],
+    );
+
+    let output = interpreter(&["run", dir.join("main.lox").to_str().unwrap()]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "3.14159\n3.14159\n3 module\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Undefined property 'missing'.\n[line 6]\n"
+    );
+    assert_eq!(output.status.code(), Some(70));
+}
+
+#[test]
+fn truthy_breakpoint_logs_the_variables_in_scope() {
+    let output = interpreter(&[
+        "run",
+        "-e",
+        "var total = 0;\n\
+         fun add(n) {\n\
+           var doubled = n * 2;\n\
+           breakpoint(n > 1);\n\
+           total = total + doubled;\n\
+         }\n\
+         add(1);\n\
+         add(2);\n\
+         print total;",
+    ]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "6\n");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "breakpoint:\n0: doubled = 4, n = 2\n1: add = <fn add>, total = 2\n"
+    );
+    assert!(output.status.success());
+}
+
+#[test]
+fn repl_lambdas_outlive_the_line_that_declared_them() {
+    let output = with_stdin(
+        &["repl"],
+        b"var add = fun (a) { return a + 1; };\nvar unused = 0;\nprint add(41);\nadd\n",
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).replace("> ", ""),
+        "42\n<fn>\n"
+    );
+}
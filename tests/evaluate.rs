@@ -0,0 +1,123 @@
+use codecrafters_interpreter::{errors::InterpreterError, run_source, value::LiteralValue};
+
+fn evaluate(source: &str) -> String {
+    run_source(source).unwrap().to_string()
+}
+
+fn evaluate_error(source: &str) -> String {
+    run_source(source).unwrap_err().to_string()
+}
+
+#[test]
+fn trailing_tokens_after_an_expression_are_rejected() {
+    assert_eq!(
+        evaluate_error("1 + 2 foo"),
+        "[line 1:7] Error: Unexpected trailing tokens."
+    );
+    assert_eq!(evaluate("1 + 2"), "3");
+}
+
+#[test]
+fn assignment_errors_carry_the_line() {
+    assert_eq!(
+        evaluate_error("\n\nundefinedVar = 3"),
+        "Undefined variable 'undefinedVar'.\n[line 3]"
+    );
+}
+
+#[test]
+fn division_by_zero_is_an_error() {
+    assert_eq!(evaluate_error("1 / 0"), "Division by zero.\n[line 1]");
+    assert_eq!(evaluate("0 / 5"), "0");
+    assert_eq!(evaluate("7 / 2"), "3.5");
+}
+
+#[test]
+fn modulo_gives_the_remainder() {
+    assert_eq!(evaluate("7 % 3"), "1");
+    assert_eq!(evaluate("10 % 2"), "0");
+    assert_eq!(evaluate("2 + 7 % 3 * 2"), "4");
+    assert_eq!(
+        evaluate_error(r#""a" % 2"#),
+        "Operands must be numbers.\n[line 1]"
+    );
+}
+
+#[test]
+fn ternary_picks_a_branch() {
+    assert_eq!(evaluate("true ? 1 : 2"), "1");
+    assert_eq!(evaluate("nil ? 1 : 2"), "2");
+    assert_eq!(evaluate("false ? 1 : true ? 3 : 4"), "3");
+    assert_eq!(evaluate("false ? 1 : false ? 3 : 4"), "4");
+}
+
+#[test]
+fn strings_compare_lexicographically() {
+    assert_eq!(evaluate(r#""apple" < "banana""#), "true");
+    assert_eq!(evaluate(r#""b" >= "ba""#), "false");
+    assert_eq!(evaluate(r#""Z" < "a""#), "true");
+    assert_eq!(evaluate(r#""same" <= "same""#), "true");
+}
+
+#[test]
+fn mixed_comparisons_are_an_error() {
+    assert_eq!(
+        evaluate_error(r#"1 < "2""#),
+        "Operands must be numbers or two strings.\n[line 1]"
+    );
+}
+
+#[test]
+fn power_evaluates_right_to_left() {
+    assert_eq!(evaluate("2 ** 3 ** 2"), "512");
+    assert_eq!(evaluate("(2 ** 3) ** 2"), "64");
+    assert_eq!(evaluate("2 ** -1"), "0.5");
+}
+
+#[test]
+fn power_requires_numbers() {
+    assert_eq!(
+        evaluate_error(r#""a" ** 2"#),
+        "Operands must be numbers.\n[line 1]"
+    );
+}
+
+#[test]
+fn bitwise_operators_work_on_integers() {
+    assert_eq!(evaluate("6 & 3"), "2");
+    assert_eq!(evaluate("4 | 1"), "5");
+    assert_eq!(evaluate("5 ^ 1"), "4");
+    assert_eq!(evaluate("1 | 2 == 3"), "true");
+}
+
+#[test]
+fn bitwise_operators_reject_fractions() {
+    assert_eq!(
+        evaluate_error("1.5 & 1"),
+        "Operands must be integers.\n[line 1]"
+    );
+}
+
+#[test]
+fn run_source_returns_the_value() {
+    assert!(matches!(
+        run_source("1 + 2 * 3"),
+        Ok(LiteralValue::Number(n)) if n == 7.0
+    ));
+}
+
+#[test]
+fn run_source_wraps_each_stage_of_errors() {
+    assert!(matches!(run_source("@"), Err(InterpreterError::Lex(_))));
+    assert!(matches!(run_source("1 +"), Err(InterpreterError::Parse(_))));
+    assert!(matches!(
+        run_source("-nil"),
+        Err(InterpreterError::Runtime(_))
+    ));
+}
+
+#[test]
+fn comma_yields_the_last_value() {
+    assert_eq!(evaluate("(1, 2, 3)"), "3");
+    assert_eq!(evaluate("(1, 2) + 1"), "3");
+}
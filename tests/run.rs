@@ -0,0 +1,819 @@
+use codecrafters_interpreter::exec_program;
+
+fn run(source: &str) -> String {
+    exec_program(source).unwrap()
+}
+
+fn run_error(source: &str) -> String {
+    exec_program(source).unwrap_err().to_string()
+}
+
+#[test]
+fn print_separates_values_with_spaces() {
+    assert_eq!(run("print 1, \"two\", true;"), "1 two true\n");
+    assert_eq!(run("print \"x =\", 1 + 2;"), "x = 3\n");
+}
+
+#[test]
+fn print_with_one_value_is_unchanged() {
+    assert_eq!(run("print \"solo\";"), "solo\n");
+}
+
+#[test]
+fn to_fixed_rounds_to_the_given_digits() {
+    assert_eq!(run("print toFixed(3.14159, 2);"), "3.14\n");
+    assert_eq!(run("print toFixed(2.5, 0), toFixed(1, 3);"), "3 1.000\n");
+}
+
+#[test]
+fn format_natives_round_exact_ties_away_from_zero() {
+    assert_eq!(
+        run("print toFixed(0.125, 2), toFixed(-2.5, 0), toFixed(1.005, 2);"),
+        "0.13 -3 1.00\n"
+    );
+    assert_eq!(
+        run("print toExponential(2.5, 0), toExponential(-1.25, 1);"),
+        "3e+0 -1.3e+0\n"
+    );
+    assert_eq!(
+        run("print toPrecision(0.125, 2), toPrecision(2.5, 1), toPrecision(9.5, 1);"),
+        "0.13 3 1e+1\n"
+    );
+}
+
+#[test]
+fn to_exponential_uses_a_signed_exponent() {
+    assert_eq!(run("print toExponential(123456, 2);"), "1.23e+5\n");
+    assert_eq!(run("print toExponential(0.00015, 1);"), "1.5e-4\n");
+}
+
+#[test]
+fn to_precision_switches_notation_like_javascript() {
+    assert_eq!(run("print toPrecision(123.456, 4);"), "123.5\n");
+    assert_eq!(run("print toPrecision(0.000123, 2);"), "0.00012\n");
+    assert_eq!(run("print toPrecision(123456, 2);"), "1.2e+5\n");
+    assert_eq!(run("print toPrecision(0.0000001234, 2);"), "1.2e-7\n");
+}
+
+#[test]
+fn format_natives_validate_the_digit_count() {
+    assert_eq!(
+        run_error("toFixed(1, -1);"),
+        "Argument to toFixed() must be a number and an integer from 0 to 100.\n[line 1]"
+    );
+    assert_eq!(
+        run_error("toPrecision(1, 0);"),
+        "Argument to toPrecision() must be a number and an integer from 1 to 100.\n[line 1]"
+    );
+    assert!(run_error("toExponential(1, 1.5);").starts_with("Argument to toExponential()"));
+}
+
+#[test]
+fn compare_orders_numbers_and_strings() {
+    assert_eq!(
+        run("print compare(1, 2), compare(2, 2), compare(3, 2);"),
+        "-1 0 1\n"
+    );
+    assert_eq!(
+        run(r#"print compare("apple", "banana"), compare("b", "a");"#),
+        "-1 1\n"
+    );
+}
+
+#[test]
+fn compare_rejects_mixed_types() {
+    assert_eq!(
+        run_error(r#"compare(1, "1");"#),
+        "Operands must be numbers or two strings.\n[line 1]"
+    );
+}
+
+const EVEN_ODD: &str = "
+    fun isEven(n) { if (n == 0) return true; return isOdd(n - 1); }
+    fun isOdd(n) { if (n == 0) return false; return isEven(n - 1); }
+";
+
+#[test]
+fn functions_are_hoisted_to_the_top_of_their_scope() {
+    let calls = "print isEven(10); print isOdd(7);";
+
+    assert_eq!(run(&format!("{EVEN_ODD}{calls}")), "true\ntrue\n");
+    assert_eq!(run(&format!("{calls}{EVEN_ODD}")), "true\ntrue\n");
+    assert_eq!(run(&format!("{{ {calls}{EVEN_ODD} }}")), "true\ntrue\n");
+}
+
+#[test]
+fn hoisted_functions_close_over_their_block() {
+    let source = "
+        var x = \"global\";
+        {
+            var x = \"local\";
+            show();
+            fun show() { print x; }
+        }
+    ";
+
+    assert_eq!(run(source), "local\n");
+}
+
+#[test]
+fn string_natives_count_unicode_scalar_values() {
+    assert_eq!(
+        run(r#"print charAt("héllo", 1), len("héllo"), len("🎉");"#),
+        "é 5 1\n"
+    );
+    assert_eq!(run(r#"print substr("a🎉é!", 1, 2);"#), "🎉é\n");
+}
+
+#[test]
+fn iterating_a_string_yields_whole_characters() {
+    let source = r#"
+        var s = "é🎉x";
+        for (var i = 0; i < len(s); i = i + 1) print charAt(s, i);
+    "#;
+
+    assert_eq!(run(source), "é\n🎉\nx\n");
+}
+
+#[test]
+fn while_loops_until_the_condition_fails() {
+    let source = "
+        var i = 0;
+        while (i < 5) {
+            var step = 1;
+            i = i + step;
+        }
+        print i;
+    ";
+
+    assert_eq!(run(source), "5\n");
+}
+
+#[test]
+fn for_loop_sums_a_range() {
+    let source = "
+        var sum = 0;
+        for (var i = 1; i <= 5; i = i + 1) sum = sum + i;
+        print sum;
+    ";
+
+    assert_eq!(run(source), "15\n");
+}
+
+#[test]
+fn for_loop_clauses_are_optional() {
+    let source = "
+        var i = 0;
+        for (; i < 3;) i = i + 1;
+        print i;
+        for (i = 10; ; i = i + 1) if (i > 11) break;
+        print i;
+        for (;;) { print \"once\"; break; }
+    ";
+
+    assert_eq!(run(source), "3\n12\nonce\n");
+}
+
+#[test]
+fn if_runs_the_matching_branch() {
+    let source = "
+        if (1 < 2) { var a = \"then\"; print a; } else print \"else\";
+        if (nil) print \"then\"; else { var b = \"else\"; print b; }
+        if (false) print \"skipped\";
+    ";
+
+    assert_eq!(run(source), "then\nelse\n");
+}
+
+#[test]
+fn assignment_takes_the_whole_logical_expression() {
+    assert_eq!(
+        run("var x = nil; var y = 2; var a; a = x or y; print a; print x or \"b\";"),
+        "2\nb\n"
+    );
+}
+
+#[test]
+fn functions_take_arguments() {
+    assert_eq!(run("fun add(a, b) { print a + b; } add(1, 2);"), "3\n");
+}
+
+#[test]
+fn calls_check_the_argument_count() {
+    assert_eq!(
+        run_error("fun add(a, b) {}\n\nadd(1);"),
+        "Expected 2 arguments but got 1.\n[line 3]"
+    );
+}
+
+#[test]
+fn return_exits_early_with_a_value() {
+    let source = "
+        fun sign(n) {
+            if (n < 0) { return \"negative\"; }
+            if (n == 0) return;
+            return \"positive\";
+            print \"unreachable\";
+        }
+        print sign(-3); print sign(0); print sign(4);
+    ";
+
+    assert_eq!(run(source), "negative\nnil\npositive\n");
+}
+
+#[test]
+fn clock_returns_increasing_numbers() {
+    assert_eq!(
+        run("var a = clock(); var b = clock(); print type(a); print b >= a;"),
+        "number\ntrue\n"
+    );
+}
+
+#[test]
+fn clock_takes_no_arguments() {
+    assert_eq!(
+        run_error("clock(1);"),
+        "Expected 0 arguments but got 1.\n[line 1]"
+    );
+}
+
+#[test]
+fn closures_keep_their_counter() {
+    let source = "
+        fun makeCounter() {
+            var count = 0;
+            fun increment() { count = count + 1; return count; }
+            return increment;
+        }
+        var counter = makeCounter();
+        print counter(); print counter(); print counter();
+        print makeCounter()();
+    ";
+
+    assert_eq!(run(source), "1\n2\n3\n1\n");
+}
+
+#[test]
+fn closures_bind_the_variable_in_scope_at_definition() {
+    let source = "
+        var a = \"global\";
+        {
+            fun showA() { print a; }
+            showA();
+            var a = \"block\";
+            showA();
+        }
+    ";
+
+    assert_eq!(run(source), "global\nglobal\n");
+}
+
+#[test]
+fn resolver_rejects_reading_a_local_in_its_initializer() {
+    assert_eq!(
+        run_error("{ var a = a; }"),
+        "[line 1:11] Error: Can't read local variable in its own initializer."
+    );
+}
+
+#[test]
+fn assigning_an_undefined_variable_reports_its_line() {
+    assert_eq!(
+        run_error("var a = 1;\n\nundefinedVar = 3;"),
+        "Undefined variable 'undefinedVar'.\n[line 3]"
+    );
+}
+
+#[test]
+fn large_whole_numbers_print_every_digit() {
+    assert_eq!(
+        run("print 10000000000000000000; print -18446744073709551616;"),
+        "10000000000000000000\n-18446744073709552000\n"
+    );
+}
+
+#[test]
+fn infinities_and_nan_print_by_name() {
+    let source = "var inf = 1e308 * 10; print inf; print -inf; print inf - inf;";
+    assert_eq!(run(source), "Infinity\n-Infinity\nNaN\n");
+}
+
+#[test]
+fn break_and_continue_only_affect_the_inner_loop() {
+    let source = "
+        for (var i = 0; i < 3; i = i + 1) {
+            for (var j = 0; j < 10; j = j + 1) {
+                if (j == 1) continue;
+                if (j == 3) break;
+                print i * 10 + j;
+            }
+        }
+    ";
+
+    assert_eq!(run(source), "0\n2\n10\n12\n20\n22\n");
+}
+
+#[test]
+fn break_outside_a_loop_is_rejected() {
+    assert_eq!(
+        run_error("break;"),
+        "[line 1:1] Error: 'break' outside loop."
+    );
+    assert_eq!(
+        run_error("while (true) { fun f() { continue; } }"),
+        "[line 1:26] Error: 'continue' outside loop."
+    );
+}
+
+#[test]
+fn nan_is_never_equal_to_itself() {
+    let source = "
+        var inf = 1e308 * 10;
+        var nan = inf - inf;
+        print nan == nan;
+        print nan != nan;
+        print inf == inf;
+    ";
+
+    assert_eq!(run(source), "false\ntrue\ntrue\n");
+}
+
+#[test]
+fn lists_are_read_and_assigned_by_index() {
+    let source = "var xs = [1, 2, 3]; print xs[1]; xs[0] = 9; print xs[0], xs[2];";
+    assert_eq!(run(source), "2\n9 3\n");
+}
+
+#[test]
+fn list_indexes_are_checked() {
+    assert_eq!(
+        run_error("var xs = [1]; print xs[1];"),
+        "Index out of bounds.\n[line 1]"
+    );
+    assert_eq!(
+        run_error("var xs = [1];\nprint xs[\"0\"];"),
+        "List index must be a number.\n[line 2]"
+    );
+}
+
+#[test]
+fn len_counts_strings_and_lists() {
+    assert_eq!(
+        run(r#"print len("hello"), len([1, 2]), len("");"#),
+        "5 2 0\n"
+    );
+}
+
+#[test]
+fn len_rejects_other_values() {
+    for argument in ["3", "true", "nil"] {
+        assert_eq!(
+            run_error(&format!("len({argument});")),
+            "Argument to len() must be a string or list.\n[line 1]"
+        );
+    }
+}
+
+#[test]
+fn only_instances_have_properties() {
+    assert_eq!(
+        run_error("var a = 1;\nprint a.b;"),
+        "Only instances have properties.\n[line 2]"
+    );
+    assert_eq!(
+        run_error("\"s\".b = 1;"),
+        "Only instances have fields.\n[line 1]"
+    );
+}
+
+#[test]
+fn classes_create_instances_with_fields_and_methods() {
+    let source = "
+        class Point {
+            sum() { return 10; }
+        }
+        var p = Point();
+        p.x = 1;
+        print p.x;
+        print p.sum();
+        print p;
+        print Point;
+    ";
+
+    assert_eq!(run(source), "1\n10\nPoint instance\nPoint\n");
+}
+
+#[test]
+fn missing_properties_are_an_error() {
+    assert_eq!(
+        run_error("class A {}\nprint A().x;"),
+        "Undefined property 'x'.\n[line 2]"
+    );
+}
+
+#[test]
+fn this_is_bound_to_the_receiver() {
+    let source = "
+        class Counter {
+            bump() { this.count = this.count + 1; return this; }
+        }
+        var c = Counter();
+        c.count = 0;
+        c.bump().bump();
+        print c.count;
+        var bump = c.bump;
+        bump();
+        print c.count;
+    ";
+
+    assert_eq!(run(source), "2\n3\n");
+}
+
+#[test]
+fn this_outside_a_class_is_rejected() {
+    assert_eq!(
+        run_error("print this;"),
+        "[line 1:7] Error: Can't use 'this' outside of a class."
+    );
+}
+
+#[test]
+fn init_runs_on_construction() {
+    let source = "
+        class Point {
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+                return;
+            }
+        }
+        var p = Point(1, 2);
+        print p.x + p.y;
+        print p.init(3, 4) == p;
+        print p.x;
+    ";
+
+    assert_eq!(run(source), "3\ntrue\n3\n");
+}
+
+#[test]
+fn init_checks_arity_and_cannot_return_a_value() {
+    assert_eq!(
+        run_error("class P { init(x) {} }\nP();"),
+        "Expected 1 arguments but got 0.\n[line 2]"
+    );
+    assert_eq!(
+        run_error("class P { init() { return 1; } }"),
+        "[line 1:20] Error: Can't return a value from an initializer."
+    );
+}
+
+#[test]
+fn super_calls_the_parent_method_on_the_same_instance() {
+    let source = "
+        class A {
+            name() { return \"A\"; }
+            describe() { return this.name(); }
+        }
+        class B < A {
+            name() { return \"B\"; }
+            describe() { return \"B via \" + super.describe(); }
+        }
+        class C < B {}
+        print C().describe();
+    ";
+
+    assert_eq!(run(source), "B via B\n");
+}
+
+#[test]
+fn inheritance_errors() {
+    assert_eq!(
+        run_error("var A = 1;\nclass B < A {}"),
+        "Superclass must be a class.\n[line 2]"
+    );
+    assert_eq!(
+        run_error("class A < A {}"),
+        "[line 1:11] Error: A class can't inherit from itself."
+    );
+    assert_eq!(
+        run_error("class A { f() { super.f(); } }"),
+        "[line 1:17] Error: Can't use 'super' in a class with no superclass."
+    );
+    assert_eq!(
+        run_error("super.f();"),
+        "[line 1:1] Error: Can't use 'super' outside of a class."
+    );
+}
+
+#[test]
+fn printf_substitutes_placeholders_without_a_newline() {
+    assert_eq!(
+        run(r#"printf("{} + {} = {}\n", 1, 2, 3); printf("no newline");"#),
+        "1 + 2 = 3\nno newline"
+    );
+}
+
+#[test]
+fn printf_checks_its_arguments() {
+    assert_eq!(
+        run_error(r#"printf("{} {}", 1);"#),
+        "printf: not enough arguments\n[line 1]"
+    );
+    assert_eq!(
+        run_error("printf(1);"),
+        "printf: first argument must be a string\n[line 1]"
+    );
+}
+
+#[test]
+fn char_at_and_substr_slice_strings() {
+    assert_eq!(
+        run(r#"print charAt("hello", 1); print substr("hello", 1, 3);"#),
+        "e\nell\n"
+    );
+    assert_eq!(run(r#"print substr("hello", 5, 0);"#), "\n");
+}
+
+#[test]
+fn string_indexes_out_of_range_are_an_error() {
+    for call in [
+        r#"charAt("hello", 5)"#,
+        r#"charAt("hello", -1)"#,
+        r#"substr("hello", 3, 3)"#,
+    ] {
+        assert_eq!(
+            run_error(&format!("print {call};")),
+            "String index out of bounds.\n[line 1]"
+        );
+    }
+}
+
+#[test]
+fn to_number_and_to_string_convert_values() {
+    assert_eq!(
+        run(r#"print toNumber("3.5") + 1; print toString(42) + "!";"#),
+        "4.5\n42!\n"
+    );
+    assert_eq!(run(r#"print toNumber(" 7 "), toNumber(2);"#), "7 2\n");
+}
+
+#[test]
+fn to_number_rejects_text_that_is_not_a_finite_number() {
+    for text in ["abc", "", "inf", "-infinity", "NaN"] {
+        assert_eq!(
+            run_error(&format!("toNumber(\"{text}\");")),
+            "Cannot convert to number.\n[line 1]"
+        );
+    }
+    assert_eq!(
+        run_error("toNumber(nil);"),
+        "Cannot convert to number.\n[line 1]"
+    );
+}
+
+#[test]
+fn passing_assertions_return_nil() {
+    assert_eq!(
+        run("print assert(1 < 2); assert(true, \"unused\");"),
+        "nil\n"
+    );
+}
+
+#[test]
+fn failing_assertions_raise_their_message() {
+    assert_eq!(run_error("assert(nil);"), "Assertion failed.\n[line 1]");
+    assert_eq!(
+        run_error("\nassert(1 > 2, \"math is broken\");"),
+        "math is broken\n[line 2]"
+    );
+}
+
+#[test]
+fn type_names_every_kind_of_value() {
+    let source = r#"
+        class A { m() {} }
+        fun f() {}
+        print type(1), type("s"), type(true), type(nil);
+        print type(f), type(clock), type(A().m), type(A), type(A());
+    "#;
+
+    assert_eq!(
+        run(source),
+        "number string boolean nil\nfunction function function class instance\n"
+    );
+}
+
+#[test]
+fn constants_can_be_read_but_not_reassigned() {
+    assert_eq!(run("const x = 1; print x + 1;"), "2\n");
+    assert_eq!(
+        run_error("const x = 1;\n{ x = 2; }"),
+        "Cannot assign to constant 'x'.\n[line 2]"
+    );
+    assert_eq!(
+        run_error("const x;"),
+        "[line 1:8] Error: const must be initialized."
+    );
+}
+
+#[test]
+fn redeclaring_a_local_is_rejected() {
+    assert_eq!(
+        run_error("{\n  var a = 1;\n  var a = 2;\n}"),
+        "[line 3:7] Error: Already a variable with this name in this scope."
+    );
+}
+
+#[test]
+fn redeclaring_a_global_is_allowed() {
+    assert_eq!(run("var a = 1; var a = 2; print a;"), "2\n");
+    assert_eq!(run("{ var a = 1; { var a = 2; print a; } }"), "2\n");
+}
+
+#[test]
+fn exec_program_captures_print_output() {
+    assert_eq!(run("print 1; print 2;"), "1\n2\n");
+    assert_eq!(run("var unused = 1;"), "");
+}
+
+#[test]
+fn functions_see_globals_from_inside_nested_blocks() {
+    let source = "
+        var greeting = \"hi\";
+        fun greet() { print greeting; }
+        {
+            var greeting = \"shadowed\";
+            { greet(); }
+        }
+        greeting = \"changed\";
+        greet();
+    ";
+
+    assert_eq!(run(source), "hi\nchanged\n");
+}
+
+#[test]
+fn commas_still_separate_call_arguments() {
+    let source = "
+        fun pair(a, b) { print a, b; }
+        pair(1, 2);
+        pair((1, 2), 3);
+        var x = 0;
+        (x = 1, x = x + 1);
+        print x;
+    ";
+
+    assert_eq!(run(source), "1 2\n2 3\n2\n");
+}
+
+#[test]
+fn postfix_increment_returns_the_old_value() {
+    assert_eq!(run("var i = 0; print i++; print i;"), "0\n1\n");
+    assert_eq!(run("var i = 5; print i--, i;"), "5 4\n");
+}
+
+#[test]
+fn only_variables_can_be_incremented() {
+    assert_eq!(
+        run_error("var a = [1];\na[0]++;"),
+        "[line 2:5] Error: Invalid increment target."
+    );
+}
+
+#[test]
+fn importing_a_missing_file_is_a_runtime_error() {
+    let error = run_error("\nimport \"does/not/exist.lox\";");
+
+    assert!(error.starts_with("Could not import 'does/not/exist.lox': "));
+    assert!(error.ends_with("\n[line 2]"));
+}
+
+#[test]
+fn byte_strings_evaluate_to_lists_of_bytes() {
+    assert_eq!(run(r#"print b"AB";"#), "[65, 66]\n");
+    assert_eq!(run(r#"print len(b"\u{e9}"), b"\n"[0];"#), "2 10\n");
+    assert_eq!(run("var b = 1; print b;"), "1\n");
+}
+
+#[test]
+fn static_assert_checks_constant_conditions_before_running() {
+    assert_eq!(
+        run(r#"static_assert(1 + 1 == 2 and "a" + "b" == "ab"); print "ok";"#),
+        "ok\n"
+    );
+    assert_eq!(
+        run_error("print \"before\";\nstatic_assert(1 > 2);"),
+        "[line 2:1] Error: Static assertion failed."
+    );
+    assert_eq!(
+        run_error("var x = 1; static_assert(x == 1);"),
+        "[line 1:12] Error: static_assert requires a constant expression."
+    );
+    assert_eq!(
+        run_error("static_assert(-\"a\");"),
+        "[line 1:1] Error: static_assert requires a constant expression."
+    );
+}
+
+#[test]
+fn lambdas_pass_directly_as_call_arguments() {
+    assert_eq!(
+        run(r#"
+fun forEach(list, f) {
+  for (var i = 0; i < len(list); i = i + 1) f(list[i]);
+}
+forEach([1, 2], fun(x){ print x; });
+
+var n = 10;
+var add = fun (a) { return a + n; };
+print add(1), add;
+"#),
+        "1\n2\n11 <fn>\n"
+    );
+}
+
+#[test]
+fn duplicate_functions_in_one_scope_are_rejected() {
+    assert_eq!(
+        run_error("fun f() { print 1; }\nf();\nfun f() { print 2; }\nf();"),
+        "[line 3:5] Error: Already a function with this name in this scope."
+    );
+    assert_eq!(
+        run_error("{\n  fun g() {}\n  fun g() {}\n}"),
+        "[line 3:7] Error: Already a function with this name in this scope."
+    );
+    assert_eq!(
+        run("fun f() { print 1; }\n{ fun f() { print 2; } f(); }\nf();"),
+        "2\n1\n"
+    );
+}
+
+#[test]
+fn size_of_grows_with_strings_and_lists() {
+    assert_eq!(run("print sizeOf(1), sizeOf(true);"), "8 8\n");
+    assert_eq!(
+        run(r#"print sizeOf("a long string of text") > sizeOf("short");"#),
+        "true\n"
+    );
+    assert_eq!(
+        run("print sizeOf([1, 2, 3, [4, 5]]) > sizeOf([1]);"),
+        "true\n"
+    );
+    assert_eq!(run("var l = [1]; l[0] = l; print sizeOf(l) > 0;"), "true\n");
+}
+
+#[test]
+fn frozen_values_can_be_read_but_not_mutated() {
+    assert_eq!(
+        run("var l = freeze([1, [2]]);\nl[1][0] = 3;\nprint l[0], len(l), l;"),
+        "1 2 [1, [3]]\n"
+    );
+    assert_eq!(
+        run(
+            "class P { get() { return this.x; } }\nvar p = P();\np.x = 1;\nfreeze(p);\nprint p.x, p.get(), freeze(5);"
+        ),
+        "1 1 5\n"
+    );
+    assert_eq!(
+        run_error("var l = [1];\nvar alias = l;\nfreeze(l);\nalias[0] = 2;"),
+        "Cannot mutate frozen value.\n[line 4]"
+    );
+    assert_eq!(
+        run_error("class P {}\nvar p = freeze(P());\np.x = 1;"),
+        "Cannot mutate frozen value.\n[line 3]"
+    );
+}
+
+#[test]
+fn lists_that_contain_themselves_print_the_cycle_as_an_ellipsis() {
+    assert_eq!(run("var l = [1]; l[0] = l; print l;"), "[[...]]\n");
+    assert_eq!(
+        run("var c = [0, 0]; c[1] = [c]; print c;"),
+        "[0, [[...]]]\n"
+    );
+    assert_eq!(run("var a = [1, 2]; print [a, a];"), "[[1, 2], [1, 2]]\n");
+}
+
+#[test]
+fn bitwise_operands_outside_the_i64_range_are_rejected() {
+    assert_eq!(
+        run_error("print 1e300 & 1;"),
+        "Operands must be integers.\n[line 1]"
+    );
+    assert_eq!(
+        run_error("print 1 | 9223372036854775808;"),
+        "Operands must be integers.\n[line 1]"
+    );
+    assert_eq!(
+        run("print -9223372036854775808 | 0 == -9223372036854775808;"),
+        "true\n"
+    );
+}
+
+#[test]
+fn double_minus_is_two_signs_unless_it_follows_a_variable() {
+    assert_eq!(run("print --5;"), "5\n");
+    assert_eq!(run("print 1--1;"), "2\n");
+    assert_eq!(run("var i = 3; print i--, i;"), "3 2\n");
+}